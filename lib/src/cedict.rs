@@ -0,0 +1,333 @@
+//! CC-CEDICT, a Chinese–English word dictionary, for showing the words a character is used in.
+//!
+//! Every line is `Traditional Simplified [pin1 yin1] /gloss/gloss/`. Lines starting with `#`
+//! are comments.
+
+use std::{
+	collections::HashMap,
+	fs::File,
+	io::{self, BufRead, BufReader},
+	path::{Path, PathBuf},
+};
+
+use flate2::read::GzDecoder;
+use thiserror::Error;
+
+use crate::vocabulary::WeightedWord;
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("Failed to open {}", path.display())]
+	Io { source: io::Error, path: PathBuf },
+	#[error("Failed to read CC-CEDICT")]
+	Read(#[from] io::Error),
+	#[error("Line {line} isn't a CC-CEDICT entry: {text}")]
+	Syntax { line: usize, text: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+	traditional: String,
+	simplified: String,
+	pinyin: String,
+	glosses: Vec<String>,
+}
+
+impl Word {
+	#[must_use]
+	pub fn traditional(&self) -> &str {
+		&self.traditional
+	}
+
+	#[must_use]
+	pub fn simplified(&self) -> &str {
+		&self.simplified
+	}
+
+	/// Syllables with tone numbers, like `xue2 sheng5`.
+	#[must_use]
+	pub fn pinyin(&self) -> &str {
+		&self.pinyin
+	}
+
+	#[must_use]
+	pub fn glosses(&self) -> &[String] {
+		&self.glosses
+	}
+
+	/// The simplified form, or the traditional one when `c` is only in that. Useful for showing
+	/// the word in the same script as the character it was looked up by.
+	#[must_use]
+	pub fn written_with(&self, c: char) -> &str {
+		if self.simplified.contains(c) {
+			&self.simplified
+		} else {
+			&self.traditional
+		}
+	}
+
+	/// Entries that only point to another entry, like "variant of 學".
+	fn is_cross_reference(&self) -> bool {
+		self.glosses.iter().all(|gloss| {
+			gloss.starts_with("variant of")
+				|| gloss.starts_with("old variant of")
+				|| gloss.starts_with("see ")
+		})
+	}
+
+	fn parse(line: &str) -> Option<Self> {
+		let (traditional, rest) = line.split_once(' ')?;
+		let (simplified, rest) = rest.split_once(' ')?;
+		let (pinyin, rest) = rest.strip_prefix('[')?.split_once(']')?;
+		let glosses = rest.trim().strip_prefix('/')?.strip_suffix('/')?;
+		Some(Self {
+			traditional: traditional.to_owned(),
+			simplified: simplified.to_owned(),
+			pinyin: pinyin.to_owned(),
+			glosses: glosses.split('/').map(str::to_owned).collect(),
+		})
+	}
+}
+
+/// The words of CC-CEDICT, indexed by the characters in them.
+#[derive(Debug, Clone, Default)]
+pub struct Cedict {
+	words: Vec<Word>,
+	/// Words of more than one character, by every character in either form.
+	by_char: HashMap<char, Vec<usize>>,
+	/// Words by their simplified and traditional forms.
+	by_word: HashMap<String, Vec<usize>>,
+	/// Position of words in a frequency list, most frequent first.
+	ranks: HashMap<String, usize>,
+}
+
+impl Cedict {
+	/// Reads the dictionary at `path`, which can be gzipped if its name ends in `.gz`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the file can't be read or a line isn't a CC-CEDICT entry.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		let file = File::open(path).map_err(|source| Error::Io {
+			source,
+			path: path.to_owned(),
+		})?;
+		if path.extension().is_some_and(|ext| ext == "gz") {
+			Self::from_reader(BufReader::new(GzDecoder::new(file)))
+		} else {
+			Self::from_reader(BufReader::new(file))
+		}
+	}
+
+	/// # Errors
+	///
+	/// Returns an error if `reader` fails or a line isn't a CC-CEDICT entry.
+	pub fn from_reader(reader: impl BufRead) -> Result<Self, Error> {
+		let mut cedict = Self::default();
+		for (index, line) in reader.lines().enumerate() {
+			let line = line?;
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let word = Word::parse(&line).ok_or_else(|| Error::Syntax {
+				line: index + 1,
+				text: line.clone(),
+			})?;
+			cedict.insert(word);
+		}
+		Ok(cedict)
+	}
+
+	fn insert(&mut self, word: Word) {
+		let i = self.words.len();
+		if word.simplified.chars().count() > 1 {
+			let mut chars: Vec<char> = word
+				.simplified
+				.chars()
+				.chain(word.traditional.chars())
+				.collect();
+			chars.sort_unstable();
+			chars.dedup();
+			for c in chars {
+				self.by_char.entry(c).or_default().push(i);
+			}
+		}
+		self.by_word
+			.entry(word.simplified.clone())
+			.or_default()
+			.push(i);
+		if word.traditional != word.simplified {
+			self.by_word
+				.entry(word.traditional.clone())
+				.or_default()
+				.push(i);
+		}
+		self.words.push(word);
+	}
+
+	/// Ranks words by a frequency list like SUBTLEX-CH: the first column of every line is a
+	/// word, most frequent first.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `reader` fails.
+	pub fn read_frequencies(&mut self, reader: impl BufRead) -> Result<(), Error> {
+		for line in reader.lines() {
+			let line = line?;
+			if let Some(word) = line.split_whitespace().next() {
+				let rank = self.ranks.len();
+				self.ranks.entry(word.to_owned()).or_insert(rank);
+			}
+		}
+		Ok(())
+	}
+
+	#[must_use]
+	pub fn words(&self) -> &[Word] {
+		&self.words
+	}
+
+	/// Every entry for `word`, written in either script.
+	#[must_use]
+	pub fn lookup(&self, word: &str) -> Vec<&Word> {
+		self.by_word
+			.get(word)
+			.into_iter()
+			.flatten()
+			.map(|i| &self.words[*i])
+			.collect()
+	}
+
+	/// How common `word` is, lower being more common, if it's in the frequency list.
+	#[must_use]
+	pub fn rank(&self, word: &Word) -> Option<usize> {
+		[&word.simplified, &word.traditional]
+			.into_iter()
+			.filter_map(|form| self.ranks.get(form))
+			.min()
+			.copied()
+	}
+
+	/// Up to `n` words of more than one character that contain `c`, most common first.
+	///
+	/// Words in the frequency list come first, by rank. Without a rank, shorter words and words
+	/// with more senses are taken to be more common. Entries that only refer to another entry
+	/// are left out.
+	#[must_use]
+	pub fn words_with(&self, c: char, n: usize) -> Vec<&Word> {
+		let mut words: Vec<&Word> = self
+			.by_char
+			.get(&c)
+			.into_iter()
+			.flatten()
+			.map(|i| &self.words[*i])
+			.filter(|word| !word.is_cross_reference())
+			.collect();
+		words.sort_by_key(|word| {
+			(
+				self.rank(word).unwrap_or(usize::MAX),
+				word.simplified.chars().count(),
+				std::cmp::Reverse(word.glosses.len()),
+			)
+		});
+		words.truncate(n);
+		words
+	}
+
+	/// Every word for [`Vocabulary`](crate::vocabulary::Vocabulary), simplified form first.
+	/// Words are weighted by their rank in the frequency list, leaving out the ones that aren't
+	/// in it, or all the same without a list.
+	pub fn weighted_words(&self) -> impl Iterator<Item = WeightedWord<'_>> {
+		self.words.iter().filter_map(|word| {
+			let weight = if self.ranks.is_empty() {
+				1.0
+			} else {
+				zipf(self.rank(word)?)
+			};
+			Some(WeightedWord::new(
+				vec![word.simplified.as_str(), word.traditional.as_str()],
+				weight,
+			))
+		})
+	}
+
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.words.is_empty()
+	}
+}
+
+/// The weight of the word at `rank` in a frequency list, by Zipf's law.
+#[allow(
+	clippy::as_conversions,
+	reason = "Ranks stay far below the point where an f64 loses precision"
+)]
+fn zipf(rank: usize) -> f64 {
+	1.0 / (rank + 1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CEDICT: &str = "\
+# CC-CEDICT
+# Community maintained free Chinese-English dictionary.
+大學 大学 [da4 xue2] /university/college/
+學 学 [xue2] /to learn/to study/school/
+學生 学生 [xue2 sheng5] /student/schoolchild/
+學習 学习 [xue2 xi2] /to learn/to study/
+化學 化学 [hua4 xue2] /chemistry/
+數學家 数学家 [shu4 xue2 jia1] /mathematician/
+斈 斈 [xue2] /old variant of 學|学[xue2]/
+";
+
+	fn cedict() -> Cedict {
+		Cedict::from_reader(CEDICT.as_bytes()).expect("valid data")
+	}
+
+	#[test]
+	fn parses_entries() {
+		let cedict = cedict();
+		assert_eq!(cedict.words().len(), 7, "comments are skipped");
+		let student = cedict.lookup("學生");
+		assert_eq!(student.len(), 1, "found by the traditional form");
+		assert_eq!(student[0].simplified(), "学生", "simplified");
+		assert_eq!(student[0].pinyin(), "xue2 sheng5", "pinyin");
+		assert_eq!(student[0].glosses(), ["student", "schoolchild"], "glosses");
+		assert!(
+			Cedict::from_reader("學生 学生 xue2 sheng5 /student/".as_bytes()).is_err(),
+			"pinyin needs brackets"
+		);
+	}
+
+	#[test]
+	fn common_words_containing_a_character() {
+		let mut cedict = cedict();
+		let words: Vec<&str> = cedict
+			.words_with('学', 3)
+			.into_iter()
+			.map(Word::simplified)
+			.collect();
+		assert_eq!(
+			words,
+			["大学", "学生", "学习"],
+			"short words with many senses first, without the character itself"
+		);
+		assert_eq!(
+			cedict.words_with('學', 10).len(),
+			5,
+			"traditional characters find the same words"
+		);
+
+		cedict
+			.read_frequencies("学习\t100\n化学\t50\n".as_bytes())
+			.expect("valid data");
+		let words: Vec<&str> = cedict
+			.words_with('学', 3)
+			.into_iter()
+			.map(Word::simplified)
+			.collect();
+		assert_eq!(words, ["学习", "化学", "大学"], "ranked words first");
+	}
+}
@@ -0,0 +1,137 @@
+//! How much of a text can be read with the characters someone knows.
+
+use std::{cmp::Reverse, collections::HashSet};
+
+use crate::import::count_characters;
+
+/// Tokens are every occurrence of a Chinese character in the text, types are the distinct
+/// characters. Everything else in the text is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+	tokens: usize,
+	known_tokens: usize,
+	known_types: usize,
+	types: usize,
+	unknown: Vec<(char, usize)>,
+}
+
+impl Coverage {
+	#[must_use]
+	pub fn analyze(text: &str, known: &HashSet<char>) -> Self {
+		let counts = count_characters(text);
+		let (known_counts, mut unknown): (Vec<_>, Vec<_>) =
+			counts.iter().partition(|(c, _)| known.contains(c));
+		unknown.sort_by_key(|(_, n)| Reverse(*n));
+		Self {
+			tokens: counts.iter().map(|(_, n)| n).sum(),
+			known_tokens: known_counts.iter().map(|(_, n)| n).sum(),
+			known_types: known_counts.len(),
+			types: counts.len(),
+			unknown,
+		}
+	}
+
+	#[must_use]
+	pub const fn tokens(&self) -> usize {
+		self.tokens
+	}
+
+	#[must_use]
+	pub const fn known_tokens(&self) -> usize {
+		self.known_tokens
+	}
+
+	#[must_use]
+	pub const fn types(&self) -> usize {
+		self.types
+	}
+
+	#[must_use]
+	pub const fn known_types(&self) -> usize {
+		self.known_types
+	}
+
+	/// The share of the running text that is known, from 0 to 1. A text without any Chinese
+	/// characters is fully covered.
+	#[must_use]
+	pub fn token_coverage(&self) -> f64 {
+		ratio(self.known_tokens, self.tokens)
+	}
+
+	/// The share of distinct characters that are known, from 0 to 1.
+	#[must_use]
+	pub fn type_coverage(&self) -> f64 {
+		ratio(self.known_types, self.types)
+	}
+
+	/// The unknown characters with their number of occurrences, most frequent first, so the
+	/// first ones are the ones that would help the most to learn.
+	#[must_use]
+	pub fn unknown(&self) -> &[(char, usize)] {
+		&self.unknown
+	}
+
+	/// How much coverage of the running text learning `occurrences` more would add.
+	#[must_use]
+	pub fn gain(&self, occurrences: usize) -> f64 {
+		ratio(occurrences, self.tokens)
+	}
+}
+
+/// `part` of `whole`, from 0 to 1. Nothing of nothing counts as all of it.
+#[must_use]
+#[allow(
+	clippy::as_conversions,
+	reason = "Counts stay far below the point where an f64 loses precision"
+)]
+pub fn ratio(part: usize, whole: usize) -> f64 {
+	if whole == 0 {
+		1.0
+	} else {
+		part as f64 / whole as f64
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn tokens_and_types() {
+		let known = HashSet::from(['我', '是', '人']);
+		let coverage = Coverage::analyze("我是中国人。我爱中国！", &known);
+		assert_eq!(
+			(coverage.tokens(), coverage.known_tokens()),
+			(9, 4),
+			"every occurrence counts, punctuation doesn't"
+		);
+		assert_eq!(
+			(coverage.types(), coverage.known_types()),
+			(6, 3),
+			"distinct characters"
+		);
+		assert!(
+			(coverage.token_coverage() - 4.0 / 9.0).abs() < f64::EPSILON,
+			"token coverage"
+		);
+		assert!(
+			(coverage.type_coverage() - 0.5).abs() < f64::EPSILON,
+			"type coverage"
+		);
+		assert_eq!(
+			coverage.unknown(),
+			[('中', 2), ('国', 2), ('爱', 1)],
+			"most frequent unknown first"
+		);
+	}
+
+	#[test]
+	fn empty_text_is_covered() {
+		let coverage = Coverage::analyze("no hanzi here", &HashSet::new());
+		assert!(
+			(coverage.token_coverage() - 1.0).abs() < f64::EPSILON,
+			"nothing to read"
+		);
+		assert!(coverage.unknown().is_empty(), "nothing unknown");
+	}
+}
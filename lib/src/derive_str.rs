@@ -1,26 +1,58 @@
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde as __serde;
+
+/// Maps the variants of a fieldless enum to and from fixed strings.
+///
+/// Every variant is written `Variant = "string"`, optionally followed by aliases that are
+/// also accepted when parsing: `Variant = "string" | "alias"`. Parsing is exact unless the
+/// enum is marked `#[ignore_case]`, which has to come before its other attributes.
+///
+/// If the first variant is marked `#[other]` and holds a `String`, it catches every string
+/// that doesn't match another variant, and parsing can no longer fail.
+///
+/// `ALL` lists every variant but the fallback. With the `serde` feature, the enum is
+/// serialized as its string.
 #[macro_export]
 macro_rules! derive_str {
     (
+        #[ignore_case]
+        $($rest:tt)*
+    ) => {
+        $crate::derive_str!(@ignore_case $($rest)*);
+    };
+    (
+        @$case:ident
         $(#[$meta:meta])*
         $vis:vis enum $ty:ident {
+            #[other]
+            $(#[$other_att:meta])*
+            $other:ident(String),
             $(
                 $(#[$att:meta])*
-                $var:ident = $s:expr
+                $var:ident = $s:literal $(| $alias:literal)*
             ),*
             $(,)?
         }
     ) => {
         $(#[$meta])*
         $vis enum $ty {
+            $(#[$other_att])*
+            $other(String),
             $(
                 $(#[$att])*
                 $var,
             )*
         }
 
-        impl ::core::convert::Into<&'static str> for &$ty {
-            fn into(self) -> &'static str {
+        impl $ty {
+            /// Every variant except the fallback, in declaration order.
+            pub const ALL: &'static [Self] = &[$($ty::$var,)*];
+
+            #[must_use]
+            pub fn as_str(&self) -> &str {
                 match self {
+                    $ty::$other(s) => s,
                     $(
                         $ty::$var => $s,
                     )*
@@ -28,23 +60,282 @@ macro_rules! derive_str {
             }
         }
 
+        impl ::core::convert::From<String> for $ty {
+            fn from(s: String) -> Self {
+                match $crate::__derive_str_lookup!($case, s.as_str(), $ty, $($var = $s $(| $alias)*),*) {
+                    Some(var) => var,
+                    None => $ty::$other(s),
+                }
+            }
+        }
+
         impl ::core::fmt::Display for $ty {
             fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> Result<(), ::core::fmt::Error> {
-                f.write_str(self.into())
+                f.write_str(self.as_str())
             }
         }
 
         impl ::core::str::FromStr for $ty {
-            type Err = String;
+            type Err = ::core::convert::Infallible;
 
             fn from_str(s: &str) -> Result<Self, Self::Err> {
-                Ok(match s {
+                Ok(Self::from(s.to_owned()))
+            }
+        }
+
+        $crate::__derive_str_serde!($ty);
+    };
+    (
+        @$case:ident
+        $(#[$meta:meta])*
+        $vis:vis enum $ty:ident {
+            $(
+                $(#[$att:meta])*
+                $var:ident = $s:literal $(| $alias:literal)*
+            ),*
+            $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $ty {
+            $(
+                $(#[$att])*
+                $var,
+            )*
+        }
+
+        impl $ty {
+            /// Every variant, in declaration order.
+            pub const ALL: &'static [Self] = &[$($ty::$var,)*];
+
+            #[must_use]
+            pub const fn as_str(&self) -> &'static str {
+                match self {
                     $(
-                        $s => $ty::$var,
+                        $ty::$var => $s,
                     )*
-                    _ => return Err(format!("bad {} string: {}", stringify!($ty), s)),
-                })
+                }
             }
         }
-    }
+
+        impl ::core::convert::From<&$ty> for &'static str {
+            fn from(var: &$ty) -> Self {
+                var.as_str()
+            }
+        }
+
+        impl ::core::fmt::Display for $ty {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> Result<(), ::core::fmt::Error> {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::core::str::FromStr for $ty {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $crate::__derive_str_lookup!($case, s, $ty, $($var = $s $(| $alias)*),*)
+                    .ok_or_else(|| format!("bad {} string: {}", stringify!($ty), s))
+            }
+        }
+
+        $crate::__derive_str_serde!($ty);
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $($rest:tt)*
+    ) => {
+        $crate::derive_str!(@exact $(#[$meta])* $vis enum $($rest)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __derive_str_lookup {
+    (exact, $s:expr, $ty:ident, $($var:ident = $name:literal $(| $alias:literal)*),*) => {
+        match $s {
+            $(
+                $name $(| $alias)* => Some($ty::$var),
+            )*
+            _ => None,
+        }
+    };
+    (ignore_case, $s:expr, $ty:ident, $($var:ident = $name:literal $(| $alias:literal)*),*) => {{
+        let s: &str = $s;
+        $(
+            if s.eq_ignore_ascii_case($name) $(|| s.eq_ignore_ascii_case($alias))* {
+                Some($ty::$var)
+            } else
+        )* {
+            None
+        }
+    }};
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __derive_str_serde {
+	($ty:ident) => {
+		impl $crate::derive_str::__serde::Serialize for $ty {
+			fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+			where
+				S: $crate::derive_str::__serde::Serializer,
+			{
+				serializer.serialize_str(self.as_str())
+			}
+		}
+
+		impl<'de> $crate::derive_str::__serde::Deserialize<'de> for $ty {
+			fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+			where
+				D: $crate::derive_str::__serde::Deserializer<'de>,
+			{
+				let s = <String as $crate::derive_str::__serde::Deserialize>::deserialize(
+					deserializer,
+				)?;
+				s.parse()
+					.map_err(<D::Error as $crate::derive_str::__serde::de::Error>::custom)
+			}
+		}
+	};
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __derive_str_serde {
+	($ty:ident) => {};
+}
+
+#[cfg(test)]
+mod tests {
+	crate::derive_str! {
+		#[ignore_case]
+		#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+		enum Script {
+			Hanzi = "hanzi" | "hanja" | "kanji",
+			Kana = "kana",
+		}
+	}
+
+	crate::derive_str! {
+		#[derive(Debug, Clone, PartialEq, Eq)]
+		enum Level {
+			#[other]
+			Custom(String),
+			Beginner = "beginner",
+			Advanced = "advanced" | "adv",
+		}
+	}
+
+	#[test]
+	fn all_lists_variants_in_order() {
+		assert_eq!(Script::ALL, [Script::Hanzi, Script::Kana], "every variant");
+		assert_eq!(
+			Level::ALL,
+			[Level::Beginner, Level::Advanced],
+			"fallback is excluded"
+		);
+	}
+
+	#[test]
+	fn as_str_and_into_use_the_main_string() {
+		assert_eq!(
+			Script::Hanzi.as_str(),
+			"hanzi",
+			"aliases are only for parsing"
+		);
+		let s: &'static str = (&Script::Kana).into();
+		assert_eq!(s, "kana", "Into<&'static str> should match as_str");
+		assert_eq!(
+			Level::Custom("expert".to_owned()).as_str(),
+			"expert",
+			"fallback keeps its string"
+		);
+	}
+
+	#[test]
+	fn display_matches_as_str() {
+		assert_eq!(Script::Hanzi.to_string(), "hanzi", "plain enum");
+		assert_eq!(
+			Level::Advanced.to_string(),
+			"advanced",
+			"enum with fallback"
+		);
+	}
+
+	#[test]
+	fn from_str_accepts_aliases() {
+		assert_eq!("kanji".parse(), Ok(Script::Hanzi), "alias");
+		assert_eq!("adv".parse(), Ok(Level::Advanced), "alias with fallback");
+	}
+
+	#[test]
+	fn ignore_case_is_opt_in() {
+		assert_eq!("KANA".parse(), Ok(Script::Kana), "marked #[ignore_case]");
+		assert_eq!("Hanja".parse(), Ok(Script::Hanzi), "aliases too");
+		assert_eq!(
+			"Adv".parse(),
+			Ok(Level::Custom("Adv".to_owned())),
+			"exact by default"
+		);
+	}
+
+	#[test]
+	fn from_str_rejects_unknown_strings() {
+		assert_eq!(
+			"emoji".parse::<Script>(),
+			Err("bad Script string: emoji".to_owned()),
+			"plain enums can't represent unknown strings"
+		);
+	}
+
+	#[test]
+	fn fallback_captures_unknown_strings() {
+		assert_eq!(
+			"Expert".parse(),
+			Ok(Level::Custom("Expert".to_owned())),
+			"FromStr keeps the input"
+		);
+		assert_eq!(
+			Level::from("expert".to_owned()),
+			Level::Custom("expert".to_owned()),
+			"From<String>"
+		);
+		assert_eq!(
+			Level::from("beginner".to_owned()),
+			Level::Beginner,
+			"From<String> still matches"
+		);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn serde_uses_the_string() {
+		assert_eq!(
+			serde_json::to_string(&Script::Hanzi).ok(),
+			Some(r#""hanzi""#.to_owned()),
+			"serialize"
+		);
+		assert_eq!(
+			serde_json::from_str::<Script>(r#""hanja""#).ok(),
+			Some(Script::Hanzi),
+			"deserialize alias"
+		);
+		assert!(
+			serde_json::from_str::<Script>(r#""emoji""#).is_err(),
+			"deserialize unknown"
+		);
+		assert_eq!(
+			serde_json::from_str::<Level>(r#""expert""#).ok(),
+			Some(Level::Custom("expert".to_owned())),
+			"deserialize into fallback"
+		);
+		assert_eq!(
+			serde_json::to_string(&Level::Custom("expert".to_owned())).ok(),
+			Some(r#""expert""#.to_owned()),
+			"serialize fallback"
+		);
+	}
 }
@@ -0,0 +1,163 @@
+//! Getting the list of characters to test on out of free-form text.
+
+use std::collections::HashMap;
+
+use unicode_normalization::char::decompose_canonical;
+
+use crate::is_chinese_character;
+
+/// The distinct Chinese characters of a text in the order they first appear, with a report of
+/// what was changed or dropped on the way.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extraction {
+	chars: Vec<char>,
+	duplicates: Vec<(char, usize)>,
+	normalized: Vec<(char, char)>,
+	variation_selectors: usize,
+}
+
+impl Extraction {
+	#[must_use]
+	pub fn chars(&self) -> &[char] {
+		&self.chars
+	}
+
+	#[must_use]
+	pub fn into_chars(self) -> Vec<char> {
+		self.chars
+	}
+
+	/// Characters that appeared more than once, with how many repeats were dropped.
+	#[must_use]
+	pub fn duplicates(&self) -> &[(char, usize)] {
+		&self.duplicates
+	}
+
+	/// The number of repeats dropped in total.
+	#[must_use]
+	pub fn duplicate_count(&self) -> usize {
+		self.duplicates.iter().map(|(_, n)| n).sum()
+	}
+
+	/// Compatibility ideographs that were replaced, and what they were replaced with.
+	#[must_use]
+	pub fn normalized(&self) -> &[(char, char)] {
+		&self.normalized
+	}
+
+	#[must_use]
+	pub const fn variation_selectors(&self) -> usize {
+		self.variation_selectors
+	}
+}
+
+/// Finds every Chinese character in `text`.
+///
+/// CJK Compatibility Ideographs are replaced by the unified ideographs they're equivalent to,
+/// and variation selectors are dropped, so `羅` and `羅` (U+F90F) count as one character.
+///
+/// ```
+/// # use lib::extract::extract;
+/// let extraction = extract("天地\u{F90F}天羅\u{FE00}");
+/// assert_eq!(extraction.chars(), ['天', '地', '羅']);
+/// assert_eq!(extraction.duplicate_count(), 2);
+/// ```
+#[must_use]
+pub fn extract(text: &str) -> Extraction {
+	let mut extraction = Extraction::default();
+	let mut repeats: HashMap<char, usize> = HashMap::new();
+	for c in text.chars() {
+		if is_variation_selector(c) {
+			extraction.variation_selectors += 1;
+			continue;
+		}
+		let unified = normalize(c);
+		if unified != c && !extraction.normalized.contains(&(c, unified)) {
+			extraction.normalized.push((c, unified));
+		}
+		if !is_chinese_character(&unified) {
+			continue;
+		}
+		if let Some(n) = repeats.get_mut(&unified) {
+			*n += 1;
+		} else {
+			repeats.insert(unified, 0);
+			extraction.chars.push(unified);
+		}
+	}
+	extraction.duplicates = extraction
+		.chars
+		.iter()
+		.filter_map(|c| Some((*c, *repeats.get(c).filter(|n| **n > 0)?)))
+		.collect();
+	extraction
+}
+
+/// Maps a CJK Compatibility Ideograph to its unified ideograph. Every other character is
+/// returned as is.
+#[must_use]
+pub fn normalize(c: char) -> char {
+	if !is_compatibility_ideograph(c) {
+		return c;
+	}
+	let mut unified = c;
+	decompose_canonical(c, |d| unified = d);
+	unified
+}
+
+#[must_use]
+pub const fn is_variation_selector(c: char) -> bool {
+	matches!(c, '\u{FE00}'..='\u{FE0F}' | '\u{E0100}'..='\u{E01EF}')
+}
+
+const fn is_compatibility_ideograph(c: char) -> bool {
+	matches!(c, '\u{F900}'..='\u{FAFF}' | '\u{2F800}'..='\u{2FA1F}')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn keeps_first_seen_order() {
+		let extraction = extract("人天人地天人");
+		assert_eq!(extraction.chars(), ['人', '天', '地'], "not sorted");
+		assert_eq!(
+			extraction.duplicates(),
+			[('人', 2), ('天', 1)],
+			"repeats per character"
+		);
+		assert_eq!(extraction.duplicate_count(), 3, "repeats in total");
+	}
+
+	#[test]
+	fn unifies_compatibility_ideographs() {
+		let extraction = extract("\u{F90F}羅\u{2F800}");
+		assert_eq!(
+			extraction.chars(),
+			['羅', '\u{4E3D}'],
+			"U+F90F is 羅, U+2F800 is U+4E3D"
+		);
+		assert_eq!(
+			extraction.normalized(),
+			[('\u{F90F}', '羅'), ('\u{2F800}', '\u{4E3D}')],
+			"replacements are reported"
+		);
+		assert_eq!(extraction.duplicate_count(), 1, "羅 after U+F90F");
+	}
+
+	#[test]
+	fn strips_variation_selectors() {
+		let extraction = extract("葛\u{E0100}葛\u{FE00}");
+		assert_eq!(extraction.chars(), ['葛'], "selectors aren't characters");
+		assert_eq!(extraction.variation_selectors(), 2, "selectors are counted");
+	}
+
+	#[test]
+	fn ignores_everything_else() {
+		assert!(
+			extract("abc, 123。かな").chars().is_empty(),
+			"latin, digits, punctuation and kana"
+		);
+	}
+}
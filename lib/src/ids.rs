@@ -0,0 +1,289 @@
+//! Ideographic Description Sequences, which spell out the components a character is built from,
+//! like `⿱⺍⿱冖子` for 学.
+//!
+//! Reads the tab-separated files of cjkvi-ids and CHISE IDS: `U+5B66<TAB>学<TAB>⿱⺍⿱冖子[GJ]`,
+//! with more sequences in further columns. Lines starting with `#` or `;;` are comments.
+
+use std::{
+	collections::{HashMap, HashSet},
+	fs::{self, File},
+	io::{self, BufRead, BufReader},
+	path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::unihan::parse_code_point;
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("Failed to open {}", path.display())]
+	Io { source: io::Error, path: PathBuf },
+	#[error("Failed to read IDS data")]
+	Read(#[from] io::Error),
+	#[error("Line {line} isn't an IDS entry: {text}")]
+	Syntax { line: usize, text: String },
+}
+
+/// Whether `c` is one of the operators that say how components are arranged, like ⿰ for side
+/// by side.
+#[must_use]
+pub const fn is_description_character(c: char) -> bool {
+	matches!(c, '\u{2FF0}'..='\u{2FFF}' | '\u{31EF}')
+}
+
+/// The components of every character, and the characters every component is in.
+#[derive(Debug, Clone, Default)]
+pub struct Ids {
+	sequences: HashMap<char, Vec<String>>,
+	/// Characters by their direct components.
+	by_component: HashMap<char, Vec<char>>,
+}
+
+impl Ids {
+	/// Reads every `.txt` file in `dir`, like cjkvi-ids' `ids.txt` or CHISE's `IDS-UCS-*.txt`.
+	/// Characters in more than one file keep the sequences of the first, by file name.
+	///
+	/// # Errors
+	///
+	/// Returns an error if a file can't be read or a line isn't an IDS entry.
+	pub fn load_dir(dir: &Path) -> Result<Self, Error> {
+		let io_error = |source, path: &Path| Error::Io {
+			source,
+			path: path.to_owned(),
+		};
+		let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+			.map_err(|source| io_error(source, dir))?
+			.map(|entry| entry.map(|entry| entry.path()))
+			.collect::<Result<_, _>>()
+			.map_err(|source| io_error(source, dir))?;
+		paths.retain(|path| {
+			path.extension()
+				.is_some_and(|ext| ext.eq_ignore_ascii_case("txt"))
+		});
+		paths.sort();
+		let mut ids = Self::default();
+		for path in paths {
+			let file = File::open(&path).map_err(|source| io_error(source, &path))?;
+			ids.read(BufReader::new(file))?;
+		}
+		Ok(ids)
+	}
+
+	/// # Errors
+	///
+	/// Returns an error if `reader` fails or a line isn't an IDS entry.
+	pub fn from_reader(reader: impl BufRead) -> Result<Self, Error> {
+		let mut ids = Self::default();
+		ids.read(reader)?;
+		Ok(ids)
+	}
+
+	/// Adds the entries in `reader`, leaving the characters that are already there alone.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `reader` fails or a line isn't an IDS entry.
+	pub fn read(&mut self, reader: impl BufRead) -> Result<(), Error> {
+		for (index, line) in reader.lines().enumerate() {
+			let line = line?;
+			if line.is_empty() || line.starts_with('#') || line.starts_with(";;") {
+				continue;
+			}
+			let syntax_error = || Error::Syntax {
+				line: index + 1,
+				text: line.clone(),
+			};
+			let mut columns = line.split('\t');
+			let (Some(code_point), Some(_)) = (columns.next(), columns.next()) else {
+				return Err(syntax_error());
+			};
+			// CHISE writes characters beyond the BMP as `U-000200A4`. Entries for characters
+			// outside Unicode, like CHISE's `CDP-8B7C`, only matter as components of other
+			// characters.
+			let code_point = match code_point.strip_prefix("U-") {
+				Some(hex) => format!("U+{hex}"),
+				None if code_point.starts_with("U+") => code_point.to_owned(),
+				None => continue,
+			};
+			let c = parse_code_point(&code_point).ok_or_else(syntax_error)?;
+			if self.sequences.contains_key(&c) {
+				continue;
+			}
+			let sequences: Vec<String> = columns
+				.map(|sequence| {
+					sequence
+						.split_once('[')
+						.map_or(sequence, |(sequence, _)| sequence)
+						.to_owned()
+				})
+				.filter(|sequence| !sequence.is_empty())
+				.collect();
+			self.insert(c, sequences);
+		}
+		Ok(())
+	}
+
+	fn insert(&mut self, c: char, sequences: Vec<String>) {
+		if let Some(sequence) = sequences.first() {
+			for component in components_of(c, sequence) {
+				self.by_component.entry(component).or_default().push(c);
+			}
+		}
+		self.sequences.insert(c, sequences);
+	}
+
+	/// Every sequence for `c`. Sources disagree on some characters, so there can be several.
+	#[must_use]
+	pub fn sequences(&self, c: char) -> &[String] {
+		self.sequences.get(&c).map_or(&[], Vec::as_slice)
+	}
+
+	/// The components `c` is directly made of, in the order of its first sequence.
+	/// Characters that can't be split any further have none.
+	#[must_use]
+	pub fn components(&self, c: char) -> Vec<char> {
+		self.sequences(c)
+			.first()
+			.map_or_else(Vec::new, |sequence| components_of(c, sequence))
+	}
+
+	/// The components of `c`, their components and so on, each once, nearest first.
+	#[must_use]
+	pub fn all_components(&self, c: char) -> Vec<char> {
+		let mut all = Vec::new();
+		let mut seen = HashSet::from([c]);
+		let mut next = 0;
+		all.extend(self.components(c));
+		seen.extend(all.iter().copied());
+		while let Some(&component) = all.get(next) {
+			next += 1;
+			for sub in self.components(component) {
+				if seen.insert(sub) {
+					all.push(sub);
+				}
+			}
+		}
+		all
+	}
+
+	/// The characters `component` is directly a part of.
+	#[must_use]
+	pub fn characters_with(&self, component: char) -> &[char] {
+		self.by_component.get(&component).map_or(&[], Vec::as_slice)
+	}
+
+	/// The characters of `set` that have `component` anywhere in them, in the order of `set`.
+	#[must_use]
+	pub fn sharing(&self, component: char, set: &[char]) -> Vec<char> {
+		set.iter()
+			.copied()
+			.filter(|c| self.all_components(*c).contains(&component))
+			.collect()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.sequences.is_empty()
+	}
+}
+
+/// The distinct components in `sequence`, leaving out `c` itself and description characters.
+/// Components outside Unicode, written as entities like `&CDP-8B7C;`, are left out too.
+fn components_of(c: char, sequence: &str) -> Vec<char> {
+	let mut components = Vec::new();
+	let mut in_entity = false;
+	for component in sequence.chars() {
+		match component {
+			'&' => in_entity = true,
+			';' if in_entity => in_entity = false,
+			_ if in_entity || is_description_character(component) || component == c => {}
+			_ => {
+				if !components.contains(&component) {
+					components.push(component);
+				}
+			}
+		}
+	}
+	components
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const IDS: &str = "\
+# cjkvi-ids excerpt
+U+5B50\t子\t子
+U+5B57\t字\t⿱宀子
+U+5B66\t学\t⿱⺍⿱冖子[GJ]\t⿱&CDP-8BBF;子[T]
+U+5B78\t學\t⿳⿲&CDP-8C5E;爻&CDP-8C5E;冖子
+U+5B5C\t孜\t⿰子攵
+U-000200A4\t𠂤\t⿱丿㠯
+CDP-8BBF\t&CDP-8BBF;\t⿱⺍冖
+";
+
+	fn ids() -> Ids {
+		Ids::from_reader(IDS.as_bytes()).expect("valid data")
+	}
+
+	#[test]
+	fn components() {
+		let ids = ids();
+		assert_eq!(ids.sequences('学').len(), 2, "both sequences");
+		assert_eq!(
+			ids.sequences('学')[0],
+			"⿱⺍⿱冖子",
+			"without the source tags"
+		);
+		assert_eq!(ids.components('学'), ['⺍', '冖', '子'], "in order");
+		assert_eq!(
+			ids.components('學'),
+			['爻', '冖', '子'],
+			"entities left out"
+		);
+		assert!(ids.components('子').is_empty(), "子 can't be split");
+		assert_eq!(ids.components('𠂤'), ['丿', '㠯'], "CHISE's U- form");
+		assert!(
+			Ids::from_reader(&b"U+5B57"[..]).is_err(),
+			"needs the character"
+		);
+
+		let mut ids = ids;
+		ids.read("U+5B66\t学\t⿱𭕄子\nU+597D\t好\t⿰女子\n".as_bytes())
+			.expect("valid data");
+		assert_eq!(
+			ids.components('学'),
+			['⺍', '冖', '子'],
+			"the first file wins"
+		);
+		assert_eq!(
+			ids.components('好'),
+			['女', '子'],
+			"new characters are added"
+		);
+	}
+
+	#[test]
+	fn characters_sharing_a_component() {
+		let ids = ids();
+		assert_eq!(
+			ids.characters_with('子'),
+			['字', '学', '學', '孜'],
+			"in file order"
+		);
+		let mut ids = ids;
+		ids.insert('好', vec!["⿰女子".to_owned()]);
+		ids.insert('㚥', vec!["⿰女好".to_owned()]);
+		assert_eq!(
+			ids.all_components('㚥'),
+			['女', '好', '子'],
+			"components of components"
+		);
+		assert_eq!(
+			ids.sharing('子', &['㚥', '女', '字']),
+			['㚥', '字'],
+			"only the ones in the set"
+		);
+	}
+}
@@ -0,0 +1,445 @@
+//! Pulls the text out of documents, so the characters in them can be tested on.
+
+use std::{
+	collections::HashMap,
+	fs,
+	io::{self, Cursor, Read},
+	path::{Path, PathBuf},
+};
+
+use quick_xml::{events::Event, Reader};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::{extract::is_variation_selector, extract::normalize, is_chinese_character};
+
+crate::derive_str! {
+	#[ignore_case]
+	/// The kinds of documents that can be imported, named by their file extension.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub enum Format {
+		Text = "txt" | "text" | "md",
+		Html = "html" | "htm" | "xhtml",
+		Epub = "epub",
+		Srt = "srt",
+		Ass = "ass" | "ssa",
+	}
+}
+
+impl Format {
+	#[must_use]
+	pub fn from_path(path: &Path) -> Option<Self> {
+		path.extension()?.to_str()?.parse().ok()
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("Failed to read {}", path.display())]
+	Io { source: io::Error, path: PathBuf },
+	#[error("Don't know how to import {}", .0.display())]
+	UnknownFormat(PathBuf),
+	#[error("Failed to open EPUB")]
+	Zip(#[from] zip::result::ZipError),
+	#[error("Failed to read EPUB")]
+	EpubIo(#[from] io::Error),
+	#[error("Failed to parse EPUB metadata")]
+	Xml(#[from] quick_xml::Error),
+	#[error("EPUB has no `{0}`")]
+	MissingEpubEntry(&'static str),
+}
+
+/// Reads the file at `path` and extracts its text, picking the format by file extension.
+///
+/// # Errors
+///
+/// Returns an error if the format isn't supported or the file can't be read.
+pub fn read_text(path: &Path) -> Result<String, Error> {
+	let format = Format::from_path(path).ok_or_else(|| Error::UnknownFormat(path.to_owned()))?;
+	let bytes = fs::read(path).map_err(|source| Error::Io {
+		source,
+		path: path.to_owned(),
+	})?;
+	extract_text(format, &bytes)
+}
+
+/// # Errors
+///
+/// Returns an error if an EPUB can't be unpacked. Other formats always succeed.
+pub fn extract_text(format: Format, bytes: &[u8]) -> Result<String, Error> {
+	Ok(match format {
+		Format::Text => decode(bytes),
+		Format::Html => html_to_text(&decode(bytes)),
+		Format::Epub => epub_to_text(bytes)?,
+		Format::Srt => srt_to_text(&decode(bytes)),
+		Format::Ass => ass_to_text(&decode(bytes)),
+	})
+}
+
+/// Every Chinese character in `text` with how often it occurs, in the order they first appear.
+/// A stable sort by count puts them in order of occurrence while keeping ties in reading order.
+///
+/// Characters are normalized the same way as by [`extract`](crate::extract::extract).
+#[must_use]
+pub fn count_characters(text: &str) -> Vec<(char, usize)> {
+	let mut counts: Vec<(char, usize)> = Vec::new();
+	let mut index = HashMap::new();
+	let chars = text
+		.chars()
+		.filter(|c| !is_variation_selector(*c))
+		.map(normalize)
+		.filter(is_chinese_character);
+	for c in chars {
+		let i = *index.entry(c).or_insert_with(|| {
+			counts.push((c, 0));
+			counts.len() - 1
+		});
+		counts[i].1 += 1;
+	}
+	counts
+}
+
+/// UTF-8, or UTF-16 with a byte order mark. Anything else is decoded lossily.
+fn decode(bytes: &[u8]) -> String {
+	let (text, _, malformed) = encoding_rs::UTF_8.decode(bytes);
+	if malformed {
+		warn!("Document isn't valid UTF-8, some characters will be lost");
+	}
+	text.into_owned()
+}
+
+/// Elements whose contents aren't part of the text. `rt` and `rp` hold ruby readings.
+const SKIPPED_ELEMENTS: [&str; 5] = ["script", "style", "rt", "rp", "head"];
+
+/// Elements that start a new line.
+const BLOCK_ELEMENTS: [&str; 16] = [
+	"br",
+	"p",
+	"div",
+	"li",
+	"tr",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"title",
+	"section",
+	"blockquote",
+	"dt",
+	"dd",
+];
+
+/// Strips tags and comments and decodes entities. This doesn't need to be a full HTML parser,
+/// only good enough to get the characters out in reading order.
+fn html_to_text(html: &str) -> String {
+	let mut text = String::with_capacity(html.len());
+	let mut skipping: Option<String> = None;
+	let mut rest = html;
+	while let Some(start) = rest.find('<') {
+		if skipping.is_none() {
+			text.push_str(&decode_entities(&rest[..start]));
+		}
+		rest = &rest[start..];
+		if let Some(comment) = rest.strip_prefix("<!--") {
+			rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+			continue;
+		}
+		let Some(end) = rest.find('>') else {
+			rest = "";
+			break;
+		};
+		let tag = &rest[1..end];
+		rest = &rest[end + 1..];
+		let closing = tag.starts_with('/');
+		let name = tag
+			.trim_start_matches('/')
+			.split(|c: char| c.is_whitespace() || c == '/')
+			.next()
+			.unwrap_or_default()
+			.to_ascii_lowercase();
+		match &skipping {
+			Some(skipped) => {
+				if closing && *skipped == name {
+					skipping = None;
+				}
+			}
+			None if !closing
+				&& !tag.ends_with('/')
+				&& SKIPPED_ELEMENTS.contains(&name.as_str()) =>
+			{
+				skipping = Some(name);
+			}
+			None if BLOCK_ELEMENTS.contains(&name.as_str()) && !text.ends_with('\n') => {
+				text.push('\n');
+			}
+			None => {}
+		}
+	}
+	if skipping.is_none() {
+		text.push_str(&decode_entities(rest));
+	}
+	text
+}
+
+fn decode_entities(s: &str) -> String {
+	let mut decoded = String::with_capacity(s.len());
+	let mut rest = s;
+	while let Some(start) = rest.find('&') {
+		decoded.push_str(&rest[..start]);
+		rest = &rest[start..];
+		let entity = rest[1..]
+			.find(';')
+			.filter(|end| *end <= 10)
+			.and_then(|end| Some((decode_entity(&rest[1..=end])?, end + 2)));
+		if let Some((c, len)) = entity {
+			decoded.push(c);
+			rest = &rest[len..];
+		} else {
+			decoded.push('&');
+			rest = &rest[1..];
+		}
+	}
+	decoded.push_str(rest);
+	decoded
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+	let number = if let Some(hex) = entity
+		.strip_prefix("#x")
+		.or_else(|| entity.strip_prefix("#X"))
+	{
+		u32::from_str_radix(hex, 16).ok()
+	} else if let Some(decimal) = entity.strip_prefix('#') {
+		decimal.parse().ok()
+	} else {
+		return match entity {
+			"amp" => Some('&'),
+			"lt" => Some('<'),
+			"gt" => Some('>'),
+			"quot" => Some('"'),
+			"apos" => Some('\''),
+			"nbsp" => Some('\u{a0}'),
+			_ => None,
+		};
+	};
+	char::from_u32(number?)
+}
+
+/// Drops the cue numbers and timings, and any formatting tags.
+fn srt_to_text(srt: &str) -> String {
+	let lines = srt
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.contains("-->") && !line.chars().all(|c| c.is_ascii_digit()));
+	let text: Vec<&str> = lines.collect();
+	strip_override_tags(&html_to_text(&text.join("\n")))
+}
+
+/// Keeps the text of the `Dialogue` lines.
+fn ass_to_text(ass: &str) -> String {
+	let lines: Vec<String> = ass
+		.lines()
+		.filter_map(|line| line.trim().strip_prefix("Dialogue:"))
+		// Text is the last of the ten fields, and the only one that may contain commas.
+		.filter_map(|fields| fields.splitn(10, ',').nth(9))
+		.map(|text| {
+			strip_override_tags(text)
+				.replace("\\N", "\n")
+				.replace("\\n", "\n")
+				.replace("\\h", " ")
+		})
+		.collect();
+	lines.join("\n")
+}
+
+/// Removes `{\an8}`-style styling overrides.
+fn strip_override_tags(s: &str) -> String {
+	let mut stripped = String::with_capacity(s.len());
+	let mut rest = s;
+	while let Some(start) = rest.find('{') {
+		stripped.push_str(&rest[..start]);
+		rest = rest[start..]
+			.find('}')
+			.map_or("", |end| &rest[start + end + 1..]);
+	}
+	stripped.push_str(rest);
+	stripped
+}
+
+/// The text of every document in the EPUB's reading order.
+fn epub_to_text(bytes: &[u8]) -> Result<String, Error> {
+	let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+	let container = read_entry(&mut archive, "META-INF/container.xml")?;
+	let package_path = xml_attributes(&container, "rootfile", &["full-path"])?
+		.into_iter()
+		.find_map(|mut attrs| attrs.pop().flatten())
+		.ok_or(Error::MissingEpubEntry("rootfile"))?;
+	let package = read_entry(&mut archive, &package_path)?;
+	let package_dir = package_path.rsplit_once('/').map_or("", |(dir, _)| dir);
+
+	let manifest: HashMap<String, String> = xml_attributes(&package, "item", &["id", "href"])?
+		.into_iter()
+		.filter_map(|mut attrs| {
+			let href = attrs.pop().flatten()?;
+			let id = attrs.pop().flatten()?;
+			Some((id, href))
+		})
+		.collect();
+	let mut text = String::new();
+	for idref in xml_attributes(&package, "itemref", &["idref"])? {
+		let Some(href) = idref
+			.first()
+			.and_then(|idref| manifest.get(idref.as_deref()?))
+		else {
+			continue;
+		};
+		let path = if package_dir.is_empty() {
+			href.clone()
+		} else {
+			format!("{package_dir}/{href}")
+		};
+		text.push_str(&html_to_text(&read_entry(&mut archive, &path)?));
+		text.push('\n');
+	}
+	Ok(text)
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<String, Error> {
+	let mut entry = archive.by_name(name)?;
+	let mut bytes = Vec::new();
+	entry.read_to_end(&mut bytes)?;
+	Ok(decode(&bytes))
+}
+
+/// The values of `attrs` on every element named `element`, ignoring namespaces.
+fn xml_attributes(
+	xml: &str,
+	element: &str,
+	attrs: &[&str],
+) -> Result<Vec<Vec<Option<String>>>, Error> {
+	let mut reader = Reader::from_str(xml);
+	let mut found = Vec::new();
+	loop {
+		match reader.read_event()? {
+			Event::Start(tag) | Event::Empty(tag)
+				if tag.local_name().as_ref() == element.as_bytes() =>
+			{
+				let values = attrs
+					.iter()
+					.map(|name| {
+						let attr = tag
+							.try_get_attribute(*name)
+							.map_err(quick_xml::Error::from)?;
+						attr.map(|attr| Ok(attr.unescape_value()?.into_owned()))
+							.transpose()
+					})
+					.collect::<Result<_, Error>>()?;
+				found.push(values);
+			}
+			Event::Eof => break,
+			_ => {}
+		}
+	}
+	Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{cmp::Reverse, io::Write};
+
+	use super::*;
+
+	#[test]
+	fn html_keeps_text_in_reading_order() {
+		let html = r#"<html><head><title>题</title><style>p { color: red }</style></head>
+			<body><p>我&amp;你</p><!-- 注释 --><p><ruby>漢<rt>かん</rt>字<rp>(</rp></ruby>&#x5B57;&#23383;</p>
+			<script>var 变量 = "<p>";</script>末</body></html>"#;
+		assert_eq!(
+			html_to_text(html).split_whitespace().collect::<Vec<_>>(),
+			["我&你", "漢字字字", "末"],
+			"tags, comments, ruby readings and scripts are dropped"
+		);
+	}
+
+	#[test]
+	fn subtitles_drop_timings_and_styling() {
+		let srt = "1\r\n00:00:01,000 --> 00:00:02,000\r\n{\\an8}<i>你好</i>\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\n再见\r\n";
+		assert_eq!(srt_to_text(srt), "你好\n再见", "srt");
+		let ass = "[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,{\\i1}你好，\\N世界\nComment: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,注释";
+		assert_eq!(ass_to_text(ass), "你好，\n世界", "ass");
+	}
+
+	#[test]
+	fn epub_follows_the_spine() {
+		let mut bytes = Vec::new();
+		{
+			let mut zip = zip::ZipWriter::new(Cursor::new(&mut bytes));
+			let options = zip::write::SimpleFileOptions::default();
+			let files = [
+				("mimetype", "application/epub+zip"),
+				(
+					"META-INF/container.xml",
+					r#"<?xml version="1.0"?><container xmlns="urn:oasis:names:tc:opendocument:xmlns:container" version="1.0"><rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles></container>"#,
+				),
+				(
+					"OEBPS/content.opf",
+					r#"<?xml version="1.0"?><package xmlns="http://www.idpf.org/2007/opf" version="3.0"><manifest><item id="a" href="text/a.xhtml" media-type="application/xhtml+xml"/><item id="b" href="text/b.xhtml" media-type="application/xhtml+xml"/></manifest><spine><itemref idref="b"/><itemref idref="a"/></spine></package>"#,
+				),
+				(
+					"OEBPS/text/a.xhtml",
+					"<html><body><p>第二</p></body></html>",
+				),
+				(
+					"OEBPS/text/b.xhtml",
+					"<html><body><p>第一</p></body></html>",
+				),
+			];
+			for (name, contents) in files {
+				zip.start_file(name, options)
+					.expect("zip should be writable");
+				zip.write_all(contents.as_bytes())
+					.expect("zip should be writable");
+			}
+			zip.finish().expect("zip should be writable");
+		}
+		let text = extract_text(Format::Epub, &bytes).expect("valid EPUB");
+		assert_eq!(
+			text.split_whitespace().collect::<Vec<_>>(),
+			["第一", "第二"],
+			"spine order, not file order"
+		);
+	}
+
+	#[test]
+	fn counts_characters_by_occurrence() {
+		let mut counts = count_characters("天下，天地。地天 abc 人");
+		assert_eq!(
+			counts,
+			[('天', 3), ('下', 1), ('地', 2), ('人', 1)],
+			"first-seen order"
+		);
+		counts.sort_by_key(|(_, n)| Reverse(*n));
+		assert_eq!(
+			counts,
+			[('天', 3), ('地', 2), ('下', 1), ('人', 1)],
+			"ties stay in first-seen order"
+		);
+	}
+
+	#[test]
+	fn format_from_extension() {
+		assert_eq!(
+			Format::from_path(Path::new("book.EPUB")),
+			Some(Format::Epub),
+			"case-insensitive"
+		);
+		assert_eq!(
+			Format::from_path(Path::new("show.ssa")),
+			Some(Format::Ass),
+			"alias"
+		);
+		assert_eq!(Format::from_path(Path::new("notes")), None, "no extension");
+	}
+}
@@ -0,0 +1,423 @@
+//! `JMdict`, a Japanese–multilingual word dictionary, for showing the words a kanji is used in.
+//!
+//! Only what's needed for that is kept: the written forms with their priority tags, the readings
+//! and the English glosses. `JMdict` spells parts of speech and other tags as entities declared
+//! in its document type, which the parser expands.
+
+use std::{
+	collections::{HashMap, HashSet},
+	fs::File,
+	io::{BufRead, BufReader, Read},
+	path::Path,
+};
+
+use flate2::read::GzDecoder;
+
+use crate::{
+	is_chinese_character,
+	load_kanjidic::{Error, Node, ParseMode, Tag, XmlStream},
+	vocabulary::WeightedWord,
+};
+
+/// Priority tags that mark a word as common in `JMdict`'s own sense: it's in the first half of
+/// the Mainichi Shimbun word list or in "Ichimango goi bunruishuu".
+const COMMON: [&str; 2] = ["news1", "ichi1"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KanjiForm {
+	text: String,
+	priority: Vec<String>,
+}
+
+impl KanjiForm {
+	#[must_use]
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+
+	/// Tags like `news1`, `ichi1` and `nf12`.
+	#[must_use]
+	pub fn priority(&self) -> &[String] {
+		&self.priority
+	}
+
+	#[must_use]
+	pub fn is_common(&self) -> bool {
+		self.priority
+			.iter()
+			.any(|tag| COMMON.contains(&tag.as_str()))
+	}
+
+	/// Which block of 500 words in the newspaper frequency list this is in, 1 being the most
+	/// frequent.
+	#[must_use]
+	pub fn frequency_band(&self) -> Option<u8> {
+		self.priority
+			.iter()
+			.find_map(|tag| tag.strip_prefix("nf")?.parse().ok())
+	}
+
+	/// Roughly how often the word is used, 1 for the most frequent band. Common words without
+	/// a band are counted as just past the last one, and other words as half as often as that.
+	#[must_use]
+	pub fn weight(&self) -> f64 {
+		match self.frequency_band() {
+			Some(band) => 1.0 / f64::from(band.max(1)),
+			None if self.is_common() => 1.0 / 49.0,
+			None => 1.0 / 98.0,
+		}
+	}
+
+	/// Lower is more common.
+	fn rank(&self) -> (bool, u8, usize) {
+		(
+			!self.is_common(),
+			self.frequency_band().unwrap_or(u8::MAX),
+			self.text.chars().count(),
+		)
+	}
+
+	fn kanji(&self) -> impl Iterator<Item = char> + '_ {
+		self.text.chars().filter(is_chinese_character)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+	seq: u64,
+	kanji: Vec<KanjiForm>,
+	readings: Vec<String>,
+	glosses: Vec<String>,
+}
+
+impl Entry {
+	#[must_use]
+	pub const fn seq(&self) -> u64 {
+		self.seq
+	}
+
+	#[must_use]
+	pub fn kanji(&self) -> &[KanjiForm] {
+		&self.kanji
+	}
+
+	#[must_use]
+	pub fn readings(&self) -> &[String] {
+		&self.readings
+	}
+
+	/// English glosses of every sense.
+	#[must_use]
+	pub fn glosses(&self) -> &[String] {
+		&self.glosses
+	}
+
+	#[must_use]
+	pub fn is_common(&self) -> bool {
+		self.kanji.iter().any(KanjiForm::is_common)
+	}
+
+	/// The most common written form with `c` in it.
+	#[must_use]
+	pub fn written_with(&self, c: char) -> Option<&KanjiForm> {
+		self.kanji
+			.iter()
+			.filter(|form| form.text.contains(c))
+			.min_by_key(|form| form.rank())
+	}
+
+	/// The most common written form whose kanji are all in `known`. Kana don't need to be
+	/// known, but the form needs at least one kanji.
+	#[must_use]
+	pub fn readable_form(&self, known: &HashSet<char>) -> Option<&KanjiForm> {
+		self.kanji
+			.iter()
+			.filter(|form| {
+				form.kanji().next().is_some() && form.kanji().all(|c| known.contains(&c))
+			})
+			.min_by_key(|form| form.rank())
+	}
+
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let mut seq = None;
+		let mut kanji = Vec::new();
+		let mut readings = Vec::new();
+		let mut glosses = Vec::new();
+		xml.children(tag, |xml, child| {
+			match child.name.as_str() {
+				"ent_seq" => seq = Some(xml.parsed_text(child)?),
+				"k_ele" => kanji.push(KanjiForm::from_xml(xml, child)?),
+				"r_ele" => {
+					xml.children(child, |xml, child| {
+						match child.name.as_str() {
+							"reb" => readings.push(xml.text(child)?),
+							"re_nokanji" | "re_restr" | "re_inf" | "re_pri" => xml.skip(child)?,
+							_ => return Ok(false),
+						}
+						Ok(true)
+					})?;
+				}
+				"sense" => {
+					xml.children(child, |xml, child| {
+						match child.name.as_str() {
+							"gloss" => {
+								xml.check_attrs(child, &["xml:lang", "g_type", "g_gend"])?;
+								let lang = child.attr("xml:lang").unwrap_or("eng");
+								let gloss = xml.content(child)?;
+								if lang == "eng" {
+									glosses.push(gloss);
+								}
+							}
+							"stagk" | "stagr" | "pos" | "xref" | "ant" | "field" | "misc"
+							| "s_inf" | "lsource" | "dial" | "example" => xml.skip(child)?,
+							_ => return Ok(false),
+						}
+						Ok(true)
+					})?;
+				}
+				_ => return Ok(false),
+			}
+			Ok(true)
+		})?;
+		Ok(Self {
+			seq: seq.ok_or_else(|| Error::MissingElement {
+				parent: tag.name.clone(),
+				element: "ent_seq",
+			})?,
+			kanji,
+			readings,
+			glosses,
+		})
+	}
+}
+
+impl KanjiForm {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let mut text = None;
+		let mut priority = Vec::new();
+		xml.children(tag, |xml, child| {
+			match child.name.as_str() {
+				"keb" => text = Some(xml.text(child)?),
+				"ke_pri" => priority.push(xml.text(child)?),
+				"ke_inf" => xml.skip(child)?,
+				_ => return Ok(false),
+			}
+			Ok(true)
+		})?;
+		Ok(Self {
+			text: text.ok_or_else(|| Error::MissingElement {
+				parent: tag.name.clone(),
+				element: "keb",
+			})?,
+			priority,
+		})
+	}
+}
+
+/// The entries of `JMdict`, indexed by the kanji in their written forms.
+#[derive(Debug, Clone, Default)]
+pub struct Jmdict {
+	entries: Vec<Entry>,
+	by_char: HashMap<char, Vec<usize>>,
+}
+
+impl Jmdict {
+	/// Reads the dictionary at `path`, which can be gzipped if its name ends in `.gz`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the file can't be read or doesn't match the schema under `mode`.
+	pub fn load(path: &Path, mode: ParseMode) -> Result<Self, Error> {
+		let file = File::open(path).map_err(Error::from_io("open"))?;
+		if path.extension().is_some_and(|ext| ext == "gz") {
+			Self::from_gzip(file, mode)
+		} else {
+			Self::from_reader(BufReader::new(file), mode)
+		}
+	}
+
+	/// Parses a gzipped `JMdict` document, decompressing it as it goes.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the document can't be read or doesn't match the schema under `mode`.
+	pub fn from_gzip(gzipped: impl Read, mode: ParseMode) -> Result<Self, Error> {
+		Self::from_reader(BufReader::new(GzDecoder::new(gzipped)), mode)
+	}
+
+	/// # Errors
+	///
+	/// Returns an error if the document can't be read or doesn't match the schema under `mode`.
+	pub fn from_reader(reader: impl BufRead, mode: ParseMode) -> Result<Self, Error> {
+		let mut xml = XmlStream::new(reader, mode);
+		let root = match xml.next()? {
+			Node::Start(root) if root.name == "JMdict" => root,
+			Node::Start(root) => {
+				return Err(Error::UnexpectedElement {
+					expected: "JMdict",
+					found: root.name,
+				})
+			}
+			Node::Text(_) | Node::End | Node::Eof => return Err(Error::UnexpectedEof),
+		};
+		xml.check_attrs(&root, &[])?;
+		let mut jmdict = Self::default();
+		xml.children(&root, |xml, child| {
+			if child.name != "entry" {
+				return Ok(false);
+			}
+			jmdict.insert(Entry::from_xml(xml, child)?);
+			Ok(true)
+		})?;
+		Ok(jmdict)
+	}
+
+	fn insert(&mut self, entry: Entry) {
+		let i = self.entries.len();
+		let mut chars: Vec<char> = entry.kanji.iter().flat_map(KanjiForm::kanji).collect();
+		chars.sort_unstable();
+		chars.dedup();
+		for c in chars {
+			self.by_char.entry(c).or_default().push(i);
+		}
+		self.entries.push(entry);
+	}
+
+	#[must_use]
+	pub fn entries(&self) -> &[Entry] {
+		&self.entries
+	}
+
+	/// Up to `n` words of more than one character written with `c`, common words first, then
+	/// by newspaper frequency and length.
+	#[must_use]
+	pub fn words_with(&self, c: char, n: usize) -> Vec<&Entry> {
+		let mut words: Vec<(&Entry, &KanjiForm)> = self
+			.by_char
+			.get(&c)
+			.into_iter()
+			.flatten()
+			.map(|i| &self.entries[*i])
+			.filter_map(|entry| Some((entry, entry.written_with(c)?)))
+			.filter(|(_, form)| form.text.chars().count() > 1)
+			.collect();
+		words.sort_by_key(|(_, form)| form.rank());
+		words.into_iter().take(n).map(|(entry, _)| entry).collect()
+	}
+
+	/// Every word with a written form made only of kanji in `known`, common words first.
+	#[must_use]
+	pub fn readable(&self, known: &HashSet<char>) -> Vec<&Entry> {
+		let mut candidates: Vec<usize> = known
+			.iter()
+			.filter_map(|c| self.by_char.get(c))
+			.flatten()
+			.copied()
+			.collect();
+		candidates.sort_unstable();
+		candidates.dedup();
+		let mut words: Vec<(&Entry, &KanjiForm)> = candidates
+			.into_iter()
+			.map(|i| &self.entries[i])
+			.filter_map(|entry| Some((entry, entry.readable_form(known)?)))
+			.collect();
+		words.sort_by_key(|(_, form)| form.rank());
+		words.into_iter().map(|(entry, _)| entry).collect()
+	}
+
+	/// Every word with kanji for [`Vocabulary`](crate::vocabulary::Vocabulary), weighted by
+	/// its most common written form.
+	pub fn weighted_words(&self) -> impl Iterator<Item = WeightedWord<'_>> {
+		self.entries.iter().filter_map(|entry| {
+			let weight = entry
+				.kanji
+				.iter()
+				.map(KanjiForm::weight)
+				.max_by(f64::total_cmp)?;
+			let forms = entry.kanji.iter().map(KanjiForm::text).collect();
+			Some(WeightedWord::new(forms, weight))
+		})
+	}
+
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const FIXTURE: &str = include_str!("../fixtures/jmdict.xml");
+
+	fn fixture() -> Jmdict {
+		Jmdict::from_reader(FIXTURE.as_bytes(), ParseMode::Strict)
+			.expect("fixture should parse strictly")
+	}
+
+	#[test]
+	fn fixture_matches_schema() {
+		let jmdict = fixture();
+		assert_eq!(jmdict.entries().len(), 5, "every entry should be read");
+		let student = &jmdict.entries()[0];
+		assert_eq!(student.seq(), 1_206_730, "ent_seq");
+		assert_eq!(student.kanji()[0].text(), "学生", "keb");
+		assert_eq!(student.readings(), ["がくせい"], "reb");
+		assert_eq!(student.glosses(), ["student"], "English glosses only");
+		assert!(student.is_common(), "news1 and ichi1");
+		assert_eq!(student.kanji()[0].frequency_band(), Some(1), "nf01");
+		assert_eq!(
+			jmdict.entries()[1].glosses(),
+			["made-up word & test entry"],
+			"predefined entities still resolve"
+		);
+		assert!(
+			Jmdict::from_reader(
+				FIXTURE.replace("<!ENTITY n ", "<!ENTITY x ").as_bytes(),
+				ParseMode::Strict
+			)
+			.is_err(),
+			"undeclared entities are an error"
+		);
+	}
+
+	#[test]
+	fn words_by_kanji() {
+		let jmdict = fixture();
+		let words: Vec<u64> = jmdict
+			.words_with('右', 10)
+			.into_iter()
+			.map(Entry::seq)
+			.collect();
+		assert_eq!(
+			words,
+			[1_582_730, 1_206_700],
+			"words of more than one character, common ones first"
+		);
+		let words: Vec<&str> = jmdict
+			.words_with('学', 10)
+			.into_iter()
+			.filter_map(|entry| Some(entry.written_with('学')?.text()))
+			.collect();
+		assert_eq!(words, ["学生", "学右"], "common words first");
+	}
+
+	#[test]
+	fn words_of_known_kanji() {
+		let jmdict = fixture();
+		let known: HashSet<char> = "右愛".chars().collect();
+		let readable: Vec<&str> = jmdict
+			.readable(&known)
+			.into_iter()
+			.filter_map(|entry| Some(entry.readable_form(&known)?.text()))
+			.collect();
+		assert_eq!(
+			readable,
+			["右", "愛する", "右て"],
+			"kana don't need to be known, and the readable form of 右手 is 右て"
+		);
+	}
+}
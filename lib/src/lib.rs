@@ -1,21 +1,34 @@
+#[cfg(feature = "cedict")]
+pub mod cedict;
+pub mod coverage;
 pub mod derive_str;
+pub mod extract;
+pub mod ids;
+pub mod import;
+#[cfg(feature = "jmdict")]
+pub mod jmdict;
 pub mod load_kanjidic;
+pub mod lookup;
+#[cfg(feature = "presets")]
+pub mod preset;
+pub mod query;
+pub mod radical;
+pub mod recommend;
+pub mod script;
+pub mod search;
+pub mod set_op;
+pub mod unihan;
+pub mod variant;
+pub mod vocabulary;
 
+/// Whether `c` is a CJK ideograph, including the compatibility ideographs but not the radicals.
+/// See [`script::CjkBlock`] for the blocks.
 #[must_use]
 pub const fn is_chinese_character(c: &char) -> bool {
-	#[allow(clippy::as_conversions, reason = "Every character is a valid u32")]
-	let c = *c as u32;
-	c >= 0x3400 && c <= 0x4DBF || // CJK Unified Ideographs Extension A
-	c >= 0x4E00 && c <= 0x9FFF || // CJK Unified Ideographs
-    c >= 0x20000 && c <= 0x2A6DF || // CJK Unified Ideographs Extension B
-    c >= 0x2A700 && c <= 0x2B73F || // CJK Unified Ideographs Extension C
-    c >= 0x2B740 && c <= 0x2B81F || // CJK Unified Ideographs Extension D
-    c >= 0x2B820 && c <= 0x2CEAF || // CJK Unified Ideographs Extension E
-    c >= 0x2CEB0 && c <= 0x2EBEF || // CJK Unified Ideographs Extension F
-    c >= 0x2EBF0 && c <= 0x2EE5F || // CJK Unified Ideographs Extension I
-    c >= 0x2F800 && c <= 0x2FA1F || // CJK Compatibility Ideographs Supplement
-    c >= 0x30000 && c <= 0x3134F || // CJK Unified Ideographs Extension G
-    c >= 0x31350 && c <= 0x323AF // CJK Unified Ideographs Extension H
+	match script::CjkBlock::of(*c) {
+		Some(block) => block.is_ideograph(),
+		None => false,
+	}
 }
 
 pub fn sort_kanji(chars: &mut [char]) {
@@ -2,9 +2,12 @@
 #![allow(unused, reason = "This is temporary")]
 
 use std::{
+	collections::{HashMap, HashSet},
 	fs::{self, File, OpenOptions},
-	io::{self, BufReader, Read, Write},
+	io::{self, BufRead, BufReader, Read, Write},
+	ops::RangeInclusive,
 	path::{Path, PathBuf},
+	str::FromStr,
 	sync::OnceLock,
 	time::Duration,
 };
@@ -12,6 +15,9 @@ use std::{
 const FORCE_INVALIDATE_CACHE: bool = false;
 
 use flate2::read::GzDecoder;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use reqwest::{Client, Method, Response, Url};
 use sha2::{Digest, Sha256};
 
@@ -19,250 +25,1094 @@ const QUALIFIER: &str = "xyz";
 const ORGANIZATION: &str = "westrom";
 const APPLICATION: &str = "hantracker";
 
-use hard_xml::XmlRead;
 use thiserror::Error;
 use tracing::{debug, warn};
 
-use crate::{sort_kanji, vec_string_to_vec_char};
+use crate::{
+	query::{CharQuery, Order},
+	sort_kanji, vec_string_to_vec_char,
+};
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "kanjidic2")]
+/// How the parser treats elements and attributes it doesn't recognise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+	/// Fail on anything unrecognised, so tests notice when the schema changes.
+	Strict,
+	/// Log and skip anything unrecognised, so a new KANJIDIC2 release doesn't stop the app from starting.
+	#[default]
+	Lenient,
+}
+
+#[derive(Debug)]
 pub struct Dict2 {
-	#[xml(child = "header")]
 	header: Dict2Header,
-	#[xml(child = "character")]
 	character: Vec<Character>,
+	/// Position of every entry in `character` by its literal.
+	index: HashMap<char, usize>,
 }
 
 impl Dict2 {
-	#[must_use]
-	#[allow(
-		clippy::missing_panics_doc,
-		reason = "I'm guaranteeing all strings are single characters"
-	)]
-	pub fn get_all_jlpt(&self) -> Vec<char> {
-		let mut chars: Vec<String> = self
-			.character
+	/// Parses a whole uncompressed KANJIDIC2 document.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the document can't be read or doesn't match the schema under `mode`.
+	pub fn from_reader(reader: impl BufRead, mode: ParseMode) -> Result<Self, Error> {
+		Self::from_character_reader(CharacterReader::new(reader, mode)?)
+	}
+
+	/// Collects every remaining entry of `characters`.
+	///
+	/// # Errors
+	///
+	/// Returns the first error encountered while reading an entry.
+	pub fn from_character_reader<R: BufRead>(
+		mut characters: CharacterReader<R>,
+	) -> Result<Self, Error> {
+		let character = characters.by_ref().collect::<Result<Vec<_>, _>>()?;
+		let index = character
 			.iter()
-			.filter(|c| c.misc.jlpt.is_some())
-			.map(|c| c.literal.clone())
+			.enumerate()
+			.filter_map(|(i, c)| Some((c.literal().chars().next()?, i)))
 			.collect();
-		chars.sort();
-		vec_string_to_vec_char(chars).expect("all strings were not single characters")
+		Ok(Self {
+			header: characters.header,
+			character,
+			index,
+		})
+	}
+
+	#[must_use]
+	pub const fn header(&self) -> &Dict2Header {
+		&self.header
+	}
+
+	#[must_use]
+	pub fn characters(&self) -> &[Character] {
+		&self.character
+	}
+
+	#[must_use]
+	pub fn get(&self, c: char) -> Option<&Character> {
+		self.index.get(&c).map(|i| &self.character[*i])
+	}
+
+	#[must_use]
+	pub fn get_all_jlpt(&self) -> Vec<char> {
+		CharQuery::new().jlpt(JLPT_LEVELS).run(self)
 	}
 
 	#[must_use]
-	#[allow(
-		clippy::missing_panics_doc,
-		reason = "I'm guaranteeing all strings are single characters"
-	)]
 	pub fn get_all_joyo(&self) -> Vec<char> {
-		let mut chars: Vec<String> = self
-			.character
-			.iter()
-			.filter(|c| c.misc.grade.is_some_and(|g| matches!(g, 1..6 | 8)))
-			.map(|c| c.literal.clone())
-			.collect();
-		chars.sort();
-		vec_string_to_vec_char(chars).expect("all strings were not single characters")
+		CharQuery::new()
+			.grades(KYOIKU_GRADES.chain([SECONDARY_JOYO_GRADE]))
+			.run(self)
 	}
 
 	#[must_use]
-	#[allow(
-		clippy::missing_panics_doc,
-		reason = "I'm guaranteeing all strings are single characters"
-	)]
 	pub fn get_all_kyoiku(&self) -> Vec<char> {
-		let mut chars: Vec<String> = self
-			.character
-			.iter()
-			.filter(|c| c.misc.grade.is_some_and(|g| matches!(g, 1..6)))
-			.map(|c| c.literal.clone())
-			.collect();
-		chars.sort();
-		vec_string_to_vec_char(chars).expect("all strings were not single characters")
+		CharQuery::new().grades(KYOIKU_GRADES).run(self)
+	}
+
+	/// The `n` most frequent characters in newspapers, most frequent first.
+	#[must_use]
+	pub fn get_most_frequent(&self, n: u64) -> Vec<char> {
+		CharQuery::new()
+			.frequency(1..=n)
+			.order(Order::Frequency)
+			.run(self)
 	}
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "header")]
-struct Dict2Header {
-	#[xml(flatten_text = "file_version")]
+/// Highest `<freq>` rank KANJIDIC2 gives out.
+pub const MAX_FREQ_RANK: u64 = 2500;
+
+/// Levels of the JLPT before it was reorganised in 2010, which is what KANJIDIC2 records.
+pub const JLPT_LEVELS: RangeInclusive<u64> = 1..=4;
+/// `<grade>` values of the elementary school years.
+pub const KYOIKU_GRADES: RangeInclusive<u64> = 1..=6;
+/// `<grade>` of the remaining Jōyō kanji, taught in secondary school.
+pub const SECONDARY_JOYO_GRADE: u64 = 8;
+
+/// Reads `<character>` entries one at a time instead of loading the whole document.
+#[derive(Debug)]
+pub struct CharacterReader<R> {
+	xml: XmlStream<R>,
+	header: Dict2Header,
+	done: bool,
+}
+
+impl<R: BufRead> CharacterReader<R> {
+	/// Reads up to and including the `<header>` of an uncompressed KANJIDIC2 document.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the document doesn't start with a `<kanjidic2>` element and its header.
+	pub fn new(reader: R, mode: ParseMode) -> Result<Self, Error> {
+		let mut xml = XmlStream::new(reader, mode);
+		match xml.next()? {
+			Node::Start(root) if root.name == "kanjidic2" => xml.check_attrs(&root, &[])?,
+			Node::Start(root) => {
+				return Err(Error::UnexpectedElement {
+					expected: "kanjidic2",
+					found: root.name,
+				})
+			}
+			Node::Text(_) | Node::End | Node::Eof => return Err(Error::UnexpectedEof),
+		}
+		let header = loop {
+			match xml.next()? {
+				Node::Start(tag) if tag.name == "header" => {
+					break Dict2Header::from_xml(&mut xml, &tag)?;
+				}
+				Node::Start(tag) => xml.unknown_element("kanjidic2", &tag)?,
+				Node::Text(_) => {}
+				Node::End | Node::Eof => {
+					return Err(Error::MissingElement {
+						parent: "kanjidic2".to_owned(),
+						element: "header",
+					})
+				}
+			}
+		};
+		Ok(Self {
+			xml,
+			header,
+			done: false,
+		})
+	}
+
+	#[must_use]
+	pub const fn header(&self) -> &Dict2Header {
+		&self.header
+	}
+
+	fn next_character(&mut self) -> Result<Option<Character>, Error> {
+		loop {
+			match self.xml.next()? {
+				Node::Start(tag) if tag.name == "character" => {
+					return Character::from_xml(&mut self.xml, &tag).map(Some);
+				}
+				Node::Start(tag) => self.xml.unknown_element("kanjidic2", &tag)?,
+				Node::Text(_) => {}
+				Node::End => return Ok(None),
+				Node::Eof => return Err(Error::UnexpectedEof),
+			}
+		}
+	}
+}
+
+impl<G: Read> CharacterReader<BufReader<GzDecoder<G>>> {
+	/// Streams a gzipped KANJIDIC2 document, decompressing it as it goes.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the document doesn't start with a `<kanjidic2>` element and its header.
+	pub fn from_gzip(gzipped: G, mode: ParseMode) -> Result<Self, Error> {
+		Self::new(BufReader::new(GzDecoder::new(gzipped)), mode)
+	}
+}
+
+impl<R: BufRead> Iterator for CharacterReader<R> {
+	type Item = Result<Character, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+		match self.next_character() {
+			Ok(Some(character)) => Some(Ok(character)),
+			Ok(None) => {
+				self.done = true;
+				None
+			}
+			Err(e) => {
+				self.done = true;
+				Some(Err(e))
+			}
+		}
+	}
+}
+
+#[derive(Debug)]
+pub struct Dict2Header {
 	file_version: u64,
-	#[xml(flatten_text = "database_version")]
 	database_version: String,
-	#[xml(flatten_text = "date_of_creation")]
 	date_of_creation: String,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "character")]
-struct Character {
-	#[xml(flatten_text = "literal")]
+impl Dict2Header {
+	#[must_use]
+	pub const fn file_version(&self) -> u64 {
+		self.file_version
+	}
+
+	#[must_use]
+	pub fn database_version(&self) -> &str {
+		&self.database_version
+	}
+
+	#[must_use]
+	pub fn date_of_creation(&self) -> &str {
+		&self.date_of_creation
+	}
+}
+
+#[derive(Debug)]
+pub struct Character {
 	literal: String,
-	#[xml(child = "codepoint")]
 	codepoint: Codepoint,
-	#[xml(child = "radical")]
 	radical: Radical,
-	#[xml(child = "misc")]
 	misc: Misc,
-	#[xml(child = "dic_number")]
 	dic_number: Option<DicNumber>,
-	#[xml(child = "query_code")]
-	query_code: QueryCode,
-	#[xml(child = "reading_meaning")]
+	query_code: Option<QueryCode>,
 	reading_meaning: Option<ReadingMeaning>,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "codepoint")]
+impl Character {
+	#[must_use]
+	pub fn literal(&self) -> &str {
+		&self.literal
+	}
+
+	#[must_use]
+	pub const fn grade(&self) -> Option<u64> {
+		self.misc.grade
+	}
+
+	#[must_use]
+	pub const fn jlpt(&self) -> Option<u64> {
+		self.misc.jlpt
+	}
+
+	/// Rank among the 2500 most used characters in newspapers.
+	#[must_use]
+	pub const fn freq(&self) -> Option<u64> {
+		self.misc.freq
+	}
+
+	/// The accepted stroke count. KANJIDIC2 lists common miscounts after it.
+	#[must_use]
+	pub fn stroke_count(&self) -> Option<u64> {
+		self.misc.stroke_count.first().copied()
+	}
+
+	#[must_use]
+	pub fn radical(&self, rad_type: &RadicalType) -> Option<u64> {
+		self.radical
+			.rad_value
+			.iter()
+			.find_map(|r| (&r.rad_type == rad_type).then_some(r.rad_value))
+	}
+
+	/// Names of the radical, for characters that are radicals themselves.
+	#[must_use]
+	pub fn radical_names(&self) -> &[String] {
+		&self.misc.rad_name
+	}
+
+	pub fn readings(&self) -> impl Iterator<Item = &Reading> {
+		self.reading_meaning
+			.iter()
+			.flat_map(|rm| &rm.rmgroup)
+			.flat_map(|group| &group.reading)
+	}
+
+	pub fn meanings(&self) -> impl Iterator<Item = &Meaning> {
+		self.reading_meaning
+			.iter()
+			.flat_map(|rm| &rm.rmgroup)
+			.flat_map(|group| &group.meaning)
+	}
+
+	/// Readings only used in names.
+	#[must_use]
+	pub fn nanori(&self) -> &[String] {
+		self.reading_meaning
+			.as_ref()
+			.map_or(&[], |rm| rm.nanori.as_slice())
+	}
+
+	/// Index numbers in dictionaries and textbooks.
+	pub fn dic_refs(&self) -> impl Iterator<Item = &DicRef> {
+		self.dic_number.iter().flat_map(|dn| &dn.dic_ref)
+	}
+
+	pub fn query_codes(&self) -> impl Iterator<Item = &QCode> {
+		self.query_code.iter().flat_map(|qc| &qc.q_code)
+	}
+
+	/// Cross-references to other characters, given as code points or dictionary index numbers.
+	pub fn variants(&self) -> impl Iterator<Item = &MiscVariant> {
+		self.misc.variant.iter()
+	}
+}
+
+#[derive(Debug)]
 struct Codepoint {
-	#[xml(child = "cp_value")]
 	cp_value: Vec<CpValue>,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "cp_value")]
+#[derive(Debug)]
 struct CpValue {
-	#[xml(attr = "cp_type")]
-	cp_type: String,
-	#[xml(text)]
+	cp_type: CodepointType,
 	value: String,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "radical")]
+#[derive(Debug)]
 struct Radical {
-	#[xml(child = "rad_value")]
 	rad_value: Vec<RadValue>,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "rad_value")]
+#[derive(Debug)]
 struct RadValue {
-	#[xml(attr = "rad_type")]
-	rad_type: String,
-	#[xml(text)]
+	rad_type: RadicalType,
 	rad_value: u64,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "misc")]
+#[derive(Debug)]
 struct Misc {
-	#[xml(flatten_text = "grade")]
 	grade: Option<u64>,
-	#[xml(flatten_text = "stroke_count")]
 	stroke_count: Vec<u64>,
-	#[xml(child = "variant")]
-	variant: Option<MiscVariant>,
-	#[xml(flatten_text = "freq")]
+	variant: Vec<MiscVariant>,
 	freq: Option<u64>,
-	#[xml(flatten_text = "jlpt")]
 	jlpt: Option<u64>,
-	#[xml(flatten_text = "rad_name")]
-	rad_name: Option<String>,
+	rad_name: Vec<String>,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "variant")]
-struct MiscVariant {
-	#[xml(attr = "var_type")]
-	var_type: String,
-	#[xml(text)]
+#[derive(Debug)]
+pub struct MiscVariant {
+	var_type: VariantType,
 	var_value: String,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "dic_number")]
+impl MiscVariant {
+	#[must_use]
+	pub const fn var_type(&self) -> &VariantType {
+		&self.var_type
+	}
+
+	#[must_use]
+	pub fn value(&self) -> &str {
+		&self.var_value
+	}
+}
+
+#[derive(Debug)]
 struct DicNumber {
-	#[xml(child = "dic_ref")]
 	dic_ref: Vec<DicRef>,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "dic_ref")]
-struct DicRef {
-	#[xml(attr = "dr_type")]
-	dr_type: String,
-	#[xml(text)]
+#[derive(Debug)]
+pub struct DicRef {
+	dr_type: DicRefType,
 	dr_value: String,
-	#[xml(attr = "m_vol")]
 	m_vol: Option<u64>,
-	#[xml(attr = "m_page")]
 	m_page: Option<u64>,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "query_code")]
+impl DicRef {
+	#[must_use]
+	pub const fn dr_type(&self) -> &DicRefType {
+		&self.dr_type
+	}
+
+	/// The index number, or for Moro's dictionary the entry number.
+	#[must_use]
+	pub fn value(&self) -> &str {
+		&self.dr_value
+	}
+
+	/// The volume and page of the entry, only given for Moro's dictionary.
+	#[must_use]
+	pub const fn volume_page(&self) -> Option<(u64, u64)> {
+		match (self.m_vol, self.m_page) {
+			(Some(volume), Some(page)) => Some((volume, page)),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Debug)]
 struct QueryCode {
-	#[xml(child = "q_code")]
 	q_code: Vec<QCode>,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "q_code")]
-struct QCode {
-	#[xml(attr = "qc_type")]
-	qc_type: String,
-	#[xml(text)]
+#[derive(Debug)]
+pub struct QCode {
+	qc_type: QueryCodeType,
 	qc_value: String,
-	#[xml(attr = "skip_misclass")]
 	skip_misclass: Option<String>,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "reading_meaning")]
+impl QCode {
+	#[must_use]
+	pub const fn qc_type(&self) -> &QueryCodeType {
+		&self.qc_type
+	}
+
+	#[must_use]
+	pub fn value(&self) -> &str {
+		&self.qc_value
+	}
+
+	/// Set when this is a SKIP code people commonly get wrong, rather than the correct one.
+	#[must_use]
+	pub fn skip_misclass(&self) -> Option<&str> {
+		self.skip_misclass.as_deref()
+	}
+}
+
+#[derive(Debug)]
 struct ReadingMeaning {
-	#[xml(child = "rmgroup")]
 	rmgroup: Vec<RMGroup>,
-	#[xml(flatten_text = "nanori")]
 	nanori: Vec<String>,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "rmgroup")]
+#[derive(Debug)]
 struct RMGroup {
-	#[xml(child = "reading")]
 	reading: Vec<Reading>,
-	#[xml(child = "meaning")]
 	meaning: Vec<Meaning>,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "meaning")]
-struct Meaning {
-	#[xml(attr = "m_lang")]
+#[derive(Debug)]
+pub struct Meaning {
 	m_lang: Option<String>,
-	#[xml(text)]
 	meaning: String,
 }
 
-#[derive(XmlRead, Debug)]
-#[xml(strict(unknown_attribute, unknown_element))]
-#[xml(tag = "reading")]
-struct Reading {
-	#[xml(attr = "r_type")]
-	r_type: String,
-	#[xml(text)]
+impl Meaning {
+	/// The ISO 639-1 code of the language, which is English when it isn't given.
+	#[must_use]
+	pub fn lang(&self) -> &str {
+		self.m_lang.as_deref().unwrap_or("en")
+	}
+
+	#[must_use]
+	pub fn value(&self) -> &str {
+		&self.meaning
+	}
+}
+
+#[derive(Debug)]
+pub struct Reading {
+	r_type: ReadingType,
 	r_value: String,
 }
 
+impl Reading {
+	#[must_use]
+	pub const fn r_type(&self) -> &ReadingType {
+		&self.r_type
+	}
+
+	#[must_use]
+	pub fn value(&self) -> &str {
+		&self.r_value
+	}
+}
+
+crate::derive_str! {
+	/// Encoding a `<cp_value>` is given in.
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	pub enum CodepointType {
+		#[other]
+		Other(String),
+		Jis208 = "jis208",
+		Jis212 = "jis212",
+		Jis213 = "jis213",
+		Ucs = "ucs",
+	}
+}
+
+crate::derive_str! {
+	/// Radical numbering system a `<rad_value>` belongs to.
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	pub enum RadicalType {
+		#[other]
+		Other(String),
+		Classical = "classical",
+		NelsonC = "nelson_c",
+	}
+}
+
+crate::derive_str! {
+	/// Coding system or encoding used to reference a `<variant>`.
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	pub enum VariantType {
+		#[other]
+		Other(String),
+		Jis208 = "jis208",
+		Jis212 = "jis212",
+		Jis213 = "jis213",
+		Deroo = "deroo",
+		Njecd = "njecd",
+		SH = "s_h",
+		NelsonC = "nelson_c",
+		Oneill = "oneill",
+		Ucs = "ucs",
+	}
+}
+
+crate::derive_str! {
+	/// Dictionary or course a `<dic_ref>` index number comes from.
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	pub enum DicRefType {
+		#[other]
+		Other(String),
+		NelsonC = "nelson_c",
+		NelsonN = "nelson_n",
+		HalpernNjecd = "halpern_njecd",
+		HalpernKkd = "halpern_kkd",
+		HalpernKkld = "halpern_kkld",
+		HalpernKkld2ed = "halpern_kkld_2ed",
+		Heisig = "heisig",
+		Heisig6 = "heisig6",
+		Gakken = "gakken",
+		OneillNames = "oneill_names",
+		OneillKk = "oneill_kk",
+		Moro = "moro",
+		Henshall = "henshall",
+		ShKk = "sh_kk",
+		ShKk2 = "sh_kk2",
+		Sakade = "sakade",
+		JfCards = "jf_cards",
+		Henshall3 = "henshall3",
+		TuttCards = "tutt_cards",
+		Crowley = "crowley",
+		KanjiInContext = "kanji_in_context",
+		BusyPeople = "busy_people",
+		KodanshaCompact = "kodansha_compact",
+		Maniette = "maniette",
+	}
+}
+
+crate::derive_str! {
+	/// Lookup code system of a `<q_code>`.
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	pub enum QueryCodeType {
+		#[other]
+		Other(String),
+		Skip = "skip",
+		ShDesc = "sh_desc",
+		FourCorner = "four_corner",
+		Deroo = "deroo",
+		Misclass = "misclass",
+	}
+}
+
+crate::derive_str! {
+	/// Language or script of a `<reading>`.
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	pub enum ReadingType {
+		#[other]
+		Other(String),
+		Pinyin = "pinyin",
+		KoreanR = "korean_r",
+		KoreanH = "korean_h",
+		Vietnam = "vietnam",
+		JaOn = "ja_on",
+		JaKun = "ja_kun",
+	}
+}
+
+/// An opening tag, with its attributes already decoded.
+#[derive(Debug)]
+pub(crate) struct Tag {
+	pub(crate) name: String,
+	attrs: Vec<(String, String)>,
+	/// `<tag/>` has no children and no matching end tag.
+	pub(crate) empty: bool,
+}
+
+impl Tag {
+	fn from_start(start: &BytesStart<'_>, empty: bool) -> Result<Self, Error> {
+		let attrs = start
+			.attributes()
+			.map(|attr| {
+				let attr = attr.map_err(quick_xml::Error::from)?;
+				let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+				Ok((key, attr.unescape_value()?.into_owned()))
+			})
+			.collect::<Result<_, Error>>()?;
+		Ok(Self {
+			name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+			attrs,
+			empty,
+		})
+	}
+
+	pub(crate) fn attr(&self, name: &str) -> Option<&str> {
+		self.attrs
+			.iter()
+			.find_map(|(key, value)| (key == name).then_some(value.as_str()))
+	}
+
+	fn required_attr(&self, name: &'static str) -> Result<String, Error> {
+		self.attr(name)
+			.map(str::to_owned)
+			.ok_or_else(|| Error::MissingAttribute {
+				element: self.name.clone(),
+				attribute: name,
+			})
+	}
+
+	fn parsed_attr<T: FromStr>(&self, name: &str) -> Result<Option<T>, Error> {
+		self.attr(name)
+			.map(|value| {
+				value.parse().map_err(|_| Error::InvalidValue {
+					element: self.name.clone(),
+					value: value.to_owned(),
+				})
+			})
+			.transpose()
+	}
+}
+
+#[derive(Debug)]
+pub(crate) enum Node {
+	Start(Tag),
+	Text(String),
+	End,
+	Eof,
+}
+
+/// A pull parser over any buffered reader, which only ever holds one event in memory.
+#[derive(Debug)]
+pub(crate) struct XmlStream<R> {
+	reader: Reader<R>,
+	buf: Vec<u8>,
+	mode: ParseMode,
+	/// Unknown `(parent, element)` pairs that have already been logged.
+	reported: HashSet<(String, String)>,
+	/// Entities declared in the document type, like `JMdict`'s `<!ENTITY n "noun (common)">`.
+	entities: HashMap<String, String>,
+}
+
+impl<R: BufRead> XmlStream<R> {
+	pub(crate) fn new(inner: R, mode: ParseMode) -> Self {
+		let mut reader = Reader::from_reader(inner);
+		reader.config_mut().trim_text(true);
+		Self {
+			reader,
+			buf: Vec::new(),
+			mode,
+			reported: HashSet::new(),
+			entities: HashMap::new(),
+		}
+	}
+
+	pub(crate) fn next(&mut self) -> Result<Node, Error> {
+		loop {
+			self.buf.clear();
+			let node = match self.reader.read_event_into(&mut self.buf)? {
+				Event::Start(start) => Node::Start(Tag::from_start(&start, false)?),
+				Event::Empty(start) => Node::Start(Tag::from_start(&start, true)?),
+				Event::End(_) => Node::End,
+				Event::Text(text) => Node::Text(
+					text.unescape_with(|name| {
+						resolve_predefined_entity(name)
+							.or_else(|| self.entities.get(name).map(String::as_str))
+					})?
+					.into_owned(),
+				),
+				Event::CData(cdata) => {
+					Node::Text(cdata.decode().map_err(quick_xml::Error::from)?.into_owned())
+				}
+				Event::Eof => Node::Eof,
+				Event::DocType(doctype) => {
+					let doctype = String::from_utf8_lossy(&doctype);
+					self.entities.extend(entity_declarations(&doctype));
+					continue;
+				}
+				Event::Decl(_) | Event::PI(_) | Event::Comment(_) => continue,
+			};
+			return Ok(node);
+		}
+	}
+
+	/// Hands every child element of `parent` to `visit`, which returns `false` for elements it doesn't know.
+	pub(crate) fn children(
+		&mut self,
+		parent: &Tag,
+		mut visit: impl FnMut(&mut Self, &Tag) -> Result<bool, Error>,
+	) -> Result<(), Error> {
+		if parent.empty {
+			return Ok(());
+		}
+		loop {
+			match self.next()? {
+				Node::Start(child) => {
+					if !visit(self, &child)? {
+						self.unknown_element(&parent.name, &child)?;
+					}
+				}
+				Node::Text(_) => {}
+				Node::End => return Ok(()),
+				Node::Eof => return Err(Error::UnexpectedEof),
+			}
+		}
+	}
+
+	/// Text content of an element that has no attributes.
+	pub(crate) fn text(&mut self, tag: &Tag) -> Result<String, Error> {
+		self.check_attrs(tag, &[])?;
+		self.content(tag)
+	}
+
+	pub(crate) fn parsed_text<T: FromStr>(&mut self, tag: &Tag) -> Result<T, Error> {
+		let text = self.text(tag)?;
+		parse(tag, text)
+	}
+
+	pub(crate) fn content(&mut self, tag: &Tag) -> Result<String, Error> {
+		let mut text = String::new();
+		if tag.empty {
+			return Ok(text);
+		}
+		loop {
+			match self.next()? {
+				Node::Start(child) => self.unknown_element(&tag.name, &child)?,
+				Node::Text(t) => text.push_str(&t),
+				Node::End => return Ok(text),
+				Node::Eof => return Err(Error::UnexpectedEof),
+			}
+		}
+	}
+
+	pub(crate) fn unknown_element(&mut self, parent: &str, child: &Tag) -> Result<(), Error> {
+		match self.mode {
+			ParseMode::Strict => Err(Error::UnknownElement {
+				parent: parent.to_owned(),
+				element: child.name.clone(),
+			}),
+			ParseMode::Lenient => {
+				if self
+					.reported
+					.insert((parent.to_owned(), child.name.clone()))
+				{
+					warn!("Skipping unknown element <{}> in <{parent}>", child.name);
+				}
+				self.skip(child)
+			}
+		}
+	}
+
+	pub(crate) fn check_attrs(&mut self, tag: &Tag, known: &[&str]) -> Result<(), Error> {
+		for (attr, _) in &tag.attrs {
+			if known.contains(&attr.as_str()) {
+				continue;
+			}
+			match self.mode {
+				ParseMode::Strict => {
+					return Err(Error::UnknownAttribute {
+						element: tag.name.clone(),
+						attribute: attr.clone(),
+					})
+				}
+				ParseMode::Lenient => {
+					if self.reported.insert((tag.name.clone(), format!("@{attr}"))) {
+						warn!("Skipping unknown attribute `{attr}` on <{}>", tag.name);
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	pub(crate) fn skip(&mut self, tag: &Tag) -> Result<(), Error> {
+		if tag.empty {
+			return Ok(());
+		}
+		let mut depth = 1_usize;
+		loop {
+			match self.next()? {
+				Node::Start(child) if !child.empty => depth += 1,
+				Node::End => {
+					depth -= 1;
+					if depth == 0 {
+						return Ok(());
+					}
+				}
+				Node::Eof => return Err(Error::UnexpectedEof),
+				Node::Start(_) | Node::Text(_) => {}
+			}
+		}
+	}
+}
+
+/// The general entities declared in a document type's internal subset, as `(name, value)`.
+fn entity_declarations(doctype: &str) -> impl Iterator<Item = (String, String)> + '_ {
+	doctype.split("<!ENTITY").skip(1).filter_map(|declaration| {
+		let (name, rest) = declaration.trim_start().split_once(char::is_whitespace)?;
+		let rest = rest.trim_start();
+		let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+		let (value, _) = rest[1..].split_once(quote)?;
+		Some((name.to_owned(), value.to_owned()))
+	})
+}
+
+fn parse<T: FromStr>(tag: &Tag, text: String) -> Result<T, Error> {
+	text.trim().parse().map_err(|_| Error::InvalidValue {
+		element: tag.name.clone(),
+		value: text,
+	})
+}
+
+fn required<T>(parent: &Tag, element: &'static str, value: Option<T>) -> Result<T, Error> {
+	value.ok_or_else(|| Error::MissingElement {
+		parent: parent.name.clone(),
+		element,
+	})
+}
+
+trait FromXml: Sized {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error>;
+}
+
+impl FromXml for Dict2Header {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let (mut file_version, mut database_version, mut date_of_creation) = (None, None, None);
+		xml.children(tag, |xml, child| {
+			match child.name.as_str() {
+				"file_version" => file_version = Some(xml.parsed_text(child)?),
+				"database_version" => database_version = Some(xml.text(child)?),
+				"date_of_creation" => date_of_creation = Some(xml.text(child)?),
+				_ => return Ok(false),
+			}
+			Ok(true)
+		})?;
+		Ok(Self {
+			file_version: required(tag, "file_version", file_version)?,
+			database_version: required(tag, "database_version", database_version)?,
+			date_of_creation: required(tag, "date_of_creation", date_of_creation)?,
+		})
+	}
+}
+
+impl FromXml for Character {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let (mut literal, mut codepoint, mut radical, mut misc) = (None, None, None, None);
+		let (mut dic_number, mut query_code, mut reading_meaning) = (None, None, None);
+		xml.children(tag, |xml, child| {
+			match child.name.as_str() {
+				"literal" => literal = Some(xml.text(child)?),
+				"codepoint" => codepoint = Some(Codepoint::from_xml(xml, child)?),
+				"radical" => radical = Some(Radical::from_xml(xml, child)?),
+				"misc" => misc = Some(Misc::from_xml(xml, child)?),
+				"dic_number" => dic_number = Some(DicNumber::from_xml(xml, child)?),
+				"query_code" => query_code = Some(QueryCode::from_xml(xml, child)?),
+				"reading_meaning" => reading_meaning = Some(ReadingMeaning::from_xml(xml, child)?),
+				_ => return Ok(false),
+			}
+			Ok(true)
+		})?;
+		Ok(Self {
+			literal: required(tag, "literal", literal)?,
+			codepoint: required(tag, "codepoint", codepoint)?,
+			radical: required(tag, "radical", radical)?,
+			misc: required(tag, "misc", misc)?,
+			dic_number,
+			query_code,
+			reading_meaning,
+		})
+	}
+}
+
+impl FromXml for Codepoint {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let mut cp_value = Vec::new();
+		xml.children(tag, |xml, child| {
+			if child.name != "cp_value" {
+				return Ok(false);
+			}
+			cp_value.push(CpValue::from_xml(xml, child)?);
+			Ok(true)
+		})?;
+		Ok(Self { cp_value })
+	}
+}
+
+impl FromXml for CpValue {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &["cp_type"])?;
+		Ok(Self {
+			cp_type: tag.required_attr("cp_type")?.into(),
+			value: xml.content(tag)?,
+		})
+	}
+}
+
+impl FromXml for Radical {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let mut rad_value = Vec::new();
+		xml.children(tag, |xml, child| {
+			if child.name != "rad_value" {
+				return Ok(false);
+			}
+			rad_value.push(RadValue::from_xml(xml, child)?);
+			Ok(true)
+		})?;
+		Ok(Self { rad_value })
+	}
+}
+
+impl FromXml for RadValue {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &["rad_type"])?;
+		Ok(Self {
+			rad_type: tag.required_attr("rad_type")?.into(),
+			rad_value: parse(tag, xml.content(tag)?)?,
+		})
+	}
+}
+
+impl FromXml for Misc {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let mut misc = Self {
+			grade: None,
+			stroke_count: Vec::new(),
+			variant: Vec::new(),
+			freq: None,
+			jlpt: None,
+			rad_name: Vec::new(),
+		};
+		xml.children(tag, |xml, child| {
+			match child.name.as_str() {
+				"grade" => misc.grade = Some(xml.parsed_text(child)?),
+				"stroke_count" => misc.stroke_count.push(xml.parsed_text(child)?),
+				"variant" => misc.variant.push(MiscVariant::from_xml(xml, child)?),
+				"freq" => misc.freq = Some(xml.parsed_text(child)?),
+				"jlpt" => misc.jlpt = Some(xml.parsed_text(child)?),
+				"rad_name" => misc.rad_name.push(xml.text(child)?),
+				_ => return Ok(false),
+			}
+			Ok(true)
+		})?;
+		Ok(misc)
+	}
+}
+
+impl FromXml for MiscVariant {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &["var_type"])?;
+		Ok(Self {
+			var_type: tag.required_attr("var_type")?.into(),
+			var_value: xml.content(tag)?,
+		})
+	}
+}
+
+impl FromXml for DicNumber {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let mut dic_ref = Vec::new();
+		xml.children(tag, |xml, child| {
+			if child.name != "dic_ref" {
+				return Ok(false);
+			}
+			dic_ref.push(DicRef::from_xml(xml, child)?);
+			Ok(true)
+		})?;
+		Ok(Self { dic_ref })
+	}
+}
+
+impl FromXml for DicRef {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &["dr_type", "m_vol", "m_page"])?;
+		Ok(Self {
+			dr_type: tag.required_attr("dr_type")?.into(),
+			m_vol: tag.parsed_attr("m_vol")?,
+			m_page: tag.parsed_attr("m_page")?,
+			dr_value: xml.content(tag)?,
+		})
+	}
+}
+
+impl FromXml for QueryCode {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let mut q_code = Vec::new();
+		xml.children(tag, |xml, child| {
+			if child.name != "q_code" {
+				return Ok(false);
+			}
+			q_code.push(QCode::from_xml(xml, child)?);
+			Ok(true)
+		})?;
+		Ok(Self { q_code })
+	}
+}
+
+impl FromXml for QCode {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &["qc_type", "skip_misclass"])?;
+		Ok(Self {
+			qc_type: tag.required_attr("qc_type")?.into(),
+			skip_misclass: tag.attr("skip_misclass").map(str::to_owned),
+			qc_value: xml.content(tag)?,
+		})
+	}
+}
+
+impl FromXml for ReadingMeaning {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let (mut rmgroup, mut nanori) = (Vec::new(), Vec::new());
+		xml.children(tag, |xml, child| {
+			match child.name.as_str() {
+				"rmgroup" => rmgroup.push(RMGroup::from_xml(xml, child)?),
+				"nanori" => nanori.push(xml.text(child)?),
+				_ => return Ok(false),
+			}
+			Ok(true)
+		})?;
+		Ok(Self { rmgroup, nanori })
+	}
+}
+
+impl FromXml for RMGroup {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &[])?;
+		let (mut reading, mut meaning) = (Vec::new(), Vec::new());
+		xml.children(tag, |xml, child| {
+			match child.name.as_str() {
+				"reading" => reading.push(Reading::from_xml(xml, child)?),
+				"meaning" => meaning.push(Meaning::from_xml(xml, child)?),
+				_ => return Ok(false),
+			}
+			Ok(true)
+		})?;
+		Ok(Self { reading, meaning })
+	}
+}
+
+impl FromXml for Meaning {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &["m_lang"])?;
+		Ok(Self {
+			m_lang: tag.attr("m_lang").map(str::to_owned),
+			meaning: xml.content(tag)?,
+		})
+	}
+}
+
+impl FromXml for Reading {
+	fn from_xml<R: BufRead>(xml: &mut XmlStream<R>, tag: &Tag) -> Result<Self, Error> {
+		xml.check_attrs(tag, &["r_type"])?;
+		Ok(Self {
+			r_type: tag.required_attr("r_type")?.into(),
+			r_value: xml.content(tag)?,
+		})
+	}
+}
+
 #[derive(Debug, Error, Default)]
 pub enum Error {
 	#[error("Io operation `{operation}` failed")]
@@ -271,7 +1121,30 @@ pub enum Error {
 		operation: String,
 	},
 	#[error("Failed to handle xml")]
-	Xml(#[from] hard_xml::XmlError),
+	Xml(#[from] quick_xml::Error),
+	#[error("Expected <{expected}> but found <{found}>")]
+	UnexpectedElement {
+		expected: &'static str,
+		found: String,
+	},
+	#[error("Unknown element <{element}> in <{parent}>")]
+	UnknownElement { parent: String, element: String },
+	#[error("Unknown attribute `{attribute}` on <{element}>")]
+	UnknownAttribute { element: String, attribute: String },
+	#[error("Missing element <{element}> in <{parent}>")]
+	MissingElement {
+		parent: String,
+		element: &'static str,
+	},
+	#[error("Missing attribute `{attribute}` on <{element}>")]
+	MissingAttribute {
+		element: String,
+		attribute: &'static str,
+	},
+	#[error("Invalid value `{value}` in <{element}>")]
+	InvalidValue { element: String, value: String },
+	#[error("Document ended unexpectedly")]
+	UnexpectedEof,
 	#[error("Failed to calculate time")]
 	Time(#[from] std::time::SystemTimeError),
 	#[error("Failed to calculate time")]
@@ -285,7 +1158,7 @@ pub enum Error {
 }
 
 impl Error {
-	fn from_io(op: &str) -> (impl FnOnce(io::Error) -> Self + use<'_>) {
+	pub(crate) fn from_io(op: &str) -> (impl FnOnce(io::Error) -> Self + use<'_>) {
 		|e: io::Error| Self::Io {
 			source: e,
 			operation: op.to_owned(),
@@ -308,19 +1181,11 @@ pub fn bootstrap_dict(data_dir: PathBuf) -> Result<Dict2, Error> {
 	load_kanji_xml_data(&xml_data_path)?;
 	let xml_file = File::open(xml_data_path).map_err(Error::from_io("open"))?;
 
-	let dicc = Dict2::from_str(&gzipped_file_to_string(xml_file)?)?;
+	let dicc =
+		Dict2::from_character_reader(CharacterReader::from_gzip(xml_file, ParseMode::Lenient)?)?;
 	Ok(dicc)
 }
 
-fn gzipped_file_to_string(xml_file: impl Read) -> Result<String, Error> {
-	let mut decoded = GzDecoder::new(xml_file);
-	let mut s = String::new();
-	decoded
-		.read_to_string(&mut s)
-		.map_err(Error::from_io("read to string"))?;
-	Ok(s)
-}
-
 fn load_kanji_xml_data(xml_data_path: impl Into<PathBuf>) -> Result<(), Error> {
 	let xml_data_path = &xml_data_path.into();
 	let xml_data_exists = fs::exists(xml_data_path).map_err(Error::from_io("check if exists"))?;
@@ -412,3 +1277,188 @@ fn response_data_to_buffer(resp: &reqwest::blocking::Response) -> Vec<u8> {
 	debug!("Request finished!");
 	buffer
 }
+
+#[cfg(test)]
+pub(crate) fn fixture() -> Dict2 {
+	Dict2::from_reader(
+		include_bytes!("../fixtures/kanjidic2.xml").as_slice(),
+		ParseMode::Strict,
+	)
+	.expect("fixture should parse strictly")
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Write;
+
+	use flate2::{write::GzEncoder, Compression};
+
+	use super::*;
+
+	const FIXTURE: &str = include_str!("../fixtures/kanjidic2.xml");
+
+	fn parse(xml: &str, mode: ParseMode) -> Result<Dict2, Error> {
+		Dict2::from_reader(xml.as_bytes(), mode)
+	}
+
+	#[test]
+	fn fixture_matches_schema() {
+		let dict = parse(FIXTURE, ParseMode::Strict).expect("fixture should parse strictly");
+		assert_eq!(dict.header().file_version(), 4, "header should be read");
+		assert_eq!(
+			dict.header().database_version(),
+			"2025-001",
+			"header should be read"
+		);
+		assert_eq!(dict.characters().len(), 11, "every entry should be read");
+		let ichi = &dict.characters()[0];
+		assert_eq!(ichi.literal(), "一", "entries should keep document order");
+		assert_eq!(ichi.misc.jlpt, Some(4), "misc should be read");
+		let reading_meaning = ichi.reading_meaning.as_ref().expect("一 has readings");
+		assert_eq!(
+			reading_meaning.nanori,
+			["かず", "はじめ"],
+			"nanori should be read"
+		);
+		assert_eq!(ichi.nanori(), ["かず", "はじめ"], "nanori accessor");
+		let moro = ichi
+			.dic_refs()
+			.find(|dic_ref| dic_ref.dr_type() == &DicRefType::Moro)
+			.expect("一 is in Moro's dictionary");
+		assert_eq!(
+			(moro.value(), moro.volume_page()),
+			("1", Some((1, 1))),
+			"volume and page should be read"
+		);
+	}
+
+	#[test]
+	fn streams_gzipped_document() {
+		let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+		encoder
+			.write_all(FIXTURE.as_bytes())
+			.expect("writing to a Vec can't fail");
+		let gzipped = encoder.finish().expect("writing to a Vec can't fail");
+
+		let reader = CharacterReader::from_gzip(gzipped.as_slice(), ParseMode::Strict)
+			.expect("header should parse");
+		assert_eq!(
+			reader.header().date_of_creation(),
+			"2025-01-01",
+			"header should be read first"
+		);
+		let literals = reader
+			.map(|c| c.map(|c| c.literal))
+			.collect::<Result<String, _>>()
+			.expect("entries should parse");
+		assert_eq!(
+			literals, "一右引悪愛圧異亜丑亙丂",
+			"entries should stream in order"
+		);
+	}
+
+	#[test]
+	fn unknown_element_is_only_fatal_when_strict() {
+		let xml = FIXTURE.replacen("<freq>2</freq>", "<freq>2</freq><shiny><new/></shiny>", 1);
+
+		let err = parse(&xml, ParseMode::Strict).expect_err("strict mode should reject <shiny>");
+		assert!(
+			matches!(&err, Error::UnknownElement { parent, element } if parent == "misc" && element == "shiny"),
+			"unexpected error: {err:?}"
+		);
+
+		let dict = parse(&xml, ParseMode::Lenient).expect("lenient mode should skip <shiny>");
+		assert_eq!(dict.characters().len(), 11, "no entries should be lost");
+		assert_eq!(
+			dict.characters()[0].misc.jlpt,
+			Some(4),
+			"siblings after <shiny> should be read"
+		);
+	}
+
+	#[test]
+	fn unknown_attribute_is_only_fatal_when_strict() {
+		let xml = FIXTURE.replacen(r#"r_type="pinyin""#, r#"r_type="pinyin" tone="1""#, 1);
+
+		let err = parse(&xml, ParseMode::Strict).expect_err("strict mode should reject `tone`");
+		assert!(
+			matches!(&err, Error::UnknownAttribute { element, attribute } if element == "reading" && attribute == "tone"),
+			"unexpected error: {err:?}"
+		);
+
+		let dict = parse(&xml, ParseMode::Lenient).expect("lenient mode should skip `tone`");
+		assert_eq!(dict.characters().len(), 11, "no entries should be lost");
+	}
+
+	#[test]
+	fn attribute_values_are_typed() {
+		let xml = FIXTURE.replacen(r#"r_type="vietnam""#, r#"r_type="cantonese""#, 1);
+		let dict = parse(&xml, ParseMode::Strict).expect("unknown values aren't schema changes");
+		let readings = &dict.characters()[0]
+			.reading_meaning
+			.as_ref()
+			.expect("一 has readings")
+			.rmgroup[0]
+			.reading;
+		assert_eq!(
+			readings[0].r_type,
+			ReadingType::Pinyin,
+			"known values should be matched"
+		);
+		assert_eq!(
+			readings[3].r_type,
+			ReadingType::Other("cantonese".to_owned()),
+			"unknown values should be kept"
+		);
+		assert_eq!(
+			readings[3].r_type.to_string(),
+			"cantonese",
+			"unknown values should round-trip"
+		);
+	}
+
+	#[test]
+	fn jlpt_presets() {
+		let dict = fixture();
+		assert_eq!(dict.get_all_jlpt().len(), 8, "all levels together");
+	}
+
+	#[test]
+	fn grade_presets() {
+		let dict = fixture();
+		assert_eq!(dict.get_all_kyoiku().len(), 7, "grade 6 is part of Kyōiku");
+		assert_eq!(dict.get_all_joyo().len(), 8, "Kyōiku and secondary school");
+	}
+
+	#[test]
+	fn frequency_preset() {
+		let dict = fixture();
+		assert_eq!(dict.get_most_frequent(3), ['一'], "ranks are absolute");
+		assert_eq!(
+			dict.get_most_frequent(600),
+			['一', '引', '悪'],
+			"most frequent first"
+		);
+		assert_eq!(
+			dict.get_most_frequent(MAX_FREQ_RANK).len(),
+			8,
+			"characters without a rank are left out"
+		);
+	}
+
+	#[test]
+	fn missing_required_element_is_an_error() {
+		let xml = FIXTURE.replacen("<literal>一</literal>", "", 1);
+		let err = parse(&xml, ParseMode::Lenient).expect_err("<literal> is required");
+		assert!(
+			matches!(
+				&err,
+				Error::MissingElement {
+					element: "literal",
+					..
+				}
+			),
+			"unexpected error: {err:?}"
+		);
+	}
+}
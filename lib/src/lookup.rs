@@ -0,0 +1,237 @@
+//! One place to look up everything known about a character, from KANJIDIC2 and Unihan.
+
+use crate::{
+	load_kanjidic::{Character, Dict2, Meaning, RadicalType, Reading, ReadingType},
+	unihan::{self, Unihan},
+};
+
+/// Looks characters up in KANJIDIC2 and, when it's loaded, Unihan.
+#[derive(Debug, Clone, Copy)]
+pub struct Lookup<'a> {
+	dict: &'a Dict2,
+	unihan: Option<&'a Unihan>,
+}
+
+impl<'a> Lookup<'a> {
+	#[must_use]
+	pub const fn new(dict: &'a Dict2) -> Self {
+		Self { dict, unihan: None }
+	}
+
+	#[must_use]
+	pub const fn with_unihan(mut self, unihan: &'a Unihan) -> Self {
+		self.unihan = Some(unihan);
+		self
+	}
+
+	/// Everything about `c`. Characters neither source has come back empty.
+	#[must_use]
+	pub fn get(&self, c: char) -> CharInfo<'a> {
+		CharInfo {
+			char: c,
+			kanjidic: self.dict.get(c),
+			unihan: self.unihan.and_then(|unihan| unihan.get(c)),
+		}
+	}
+}
+
+/// A character's entries in KANJIDIC2 and Unihan. Where both have the same kind of data, the
+/// accessors prefer Unihan for Chinese, Korean and Vietnamese, and KANJIDIC2 for everything
+/// else.
+#[derive(Debug, Clone, Copy)]
+pub struct CharInfo<'a> {
+	char: char,
+	kanjidic: Option<&'a Character>,
+	unihan: Option<&'a unihan::Entry>,
+}
+
+impl<'a> CharInfo<'a> {
+	#[must_use]
+	pub const fn char(&self) -> char {
+		self.char
+	}
+
+	#[must_use]
+	pub const fn kanjidic(&self) -> Option<&'a Character> {
+		self.kanjidic
+	}
+
+	#[must_use]
+	pub const fn unihan(&self) -> Option<&'a unihan::Entry> {
+		self.unihan
+	}
+
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.kanjidic.is_none() && self.unihan.is_none()
+	}
+
+	fn kanjidic_readings(&self, r_type: &ReadingType) -> Vec<&'a str> {
+		self.kanjidic
+			.into_iter()
+			.flat_map(Character::readings)
+			.filter(|reading| reading.r_type() == r_type)
+			.map(Reading::value)
+			.collect()
+	}
+
+	/// Unihan's readings if it has any, otherwise KANJIDIC2's.
+	fn prefer_unihan(
+		&self,
+		unihan: fn(&'a unihan::Entry) -> &'a [String],
+		r_type: &ReadingType,
+	) -> Vec<&'a str> {
+		match self.unihan.map(unihan) {
+			Some(readings) if !readings.is_empty() => readings.iter().map(String::as_str).collect(),
+			_ => self.kanjidic_readings(r_type),
+		}
+	}
+
+	/// Pinyin, with tone marks from Unihan or tone numbers from KANJIDIC2.
+	#[must_use]
+	pub fn mandarin(&self) -> Vec<&'a str> {
+		self.prefer_unihan(unihan::Entry::mandarin, &ReadingType::Pinyin)
+	}
+
+	/// Jyutping. Only Unihan has these.
+	#[must_use]
+	pub fn cantonese(&self) -> Vec<&'a str> {
+		self.unihan
+			.into_iter()
+			.flat_map(unihan::Entry::cantonese)
+			.map(String::as_str)
+			.collect()
+	}
+
+	#[must_use]
+	pub fn hangul(&self) -> Vec<&'a str> {
+		self.prefer_unihan(unihan::Entry::hangul, &ReadingType::KoreanH)
+	}
+
+	#[must_use]
+	pub fn korean_romanized(&self) -> Vec<&'a str> {
+		self.prefer_unihan(unihan::Entry::korean, &ReadingType::KoreanR)
+	}
+
+	#[must_use]
+	pub fn vietnamese(&self) -> Vec<&'a str> {
+		self.prefer_unihan(unihan::Entry::vietnamese, &ReadingType::Vietnam)
+	}
+
+	#[must_use]
+	pub fn on(&self) -> Vec<&'a str> {
+		self.kanjidic_readings(&ReadingType::JaOn)
+	}
+
+	#[must_use]
+	pub fn kun(&self) -> Vec<&'a str> {
+		self.kanjidic_readings(&ReadingType::JaKun)
+	}
+
+	/// English meanings from KANJIDIC2, or the senses of Unihan's definition for characters
+	/// KANJIDIC2 has no meanings for.
+	#[must_use]
+	pub fn meanings(&self) -> Vec<&'a str> {
+		let kanjidic: Vec<&str> = self
+			.kanjidic
+			.into_iter()
+			.flat_map(Character::meanings)
+			.filter(|meaning| meaning.lang() == "en")
+			.map(Meaning::value)
+			.collect();
+		if !kanjidic.is_empty() {
+			return kanjidic;
+		}
+		self.unihan
+			.and_then(unihan::Entry::definition)
+			.into_iter()
+			.flat_map(|definition| definition.split(';'))
+			.map(str::trim)
+			.filter(|sense| !sense.is_empty())
+			.collect()
+	}
+
+	#[must_use]
+	pub fn stroke_count(&self) -> Option<u64> {
+		self.kanjidic
+			.and_then(Character::stroke_count)
+			.or_else(|| self.unihan?.total_strokes().first().copied())
+	}
+
+	/// The number of the Kangxi radical the character is filed under.
+	#[must_use]
+	pub fn radical(&self) -> Option<u64> {
+		self.kanjidic
+			.and_then(|c| c.radical(&RadicalType::Classical))
+			.or_else(|| {
+				let radical_stroke = self.unihan?.radical_strokes().first()?;
+				Some(radical_stroke.radical())
+			})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::load_kanjidic::fixture;
+
+	const UNIHAN: &str = "\
+U+4E00\tkMandarin\tyī
+U+4E00\tkDefinition\tone; a, an; alone
+U+5B66\tkDefinition\tlearning, knowledge; school
+U+5B66\tkHangul\t학:0N
+U+5B66\tkTotalStrokes\t8
+U+5B66\tkRSUnicode\t39.5
+";
+
+	fn unihan() -> Unihan {
+		let mut unihan = Unihan::new();
+		unihan.read(UNIHAN.as_bytes()).expect("valid data");
+		unihan
+	}
+
+	#[test]
+	fn kanjidic_only() {
+		let dict = fixture();
+		let one = Lookup::new(&dict).get('一');
+		assert_eq!(one.mandarin(), ["yi1"], "pinyin from KANJIDIC2");
+		assert_eq!(one.hangul(), ["일"], "hangul from KANJIDIC2");
+		assert_eq!(one.on(), ["イチ", "イツ"], "on'yomi");
+		assert_eq!(
+			one.meanings(),
+			["one", "one radical (no.1)"],
+			"English only"
+		);
+		assert!(
+			Lookup::new(&dict).get('学').is_empty(),
+			"not in the fixture"
+		);
+	}
+
+	#[test]
+	fn unihan_fills_in_and_takes_over() {
+		let dict = fixture();
+		let unihan = unihan();
+		let lookup = Lookup::new(&dict).with_unihan(&unihan);
+		let one = lookup.get('一');
+		assert_eq!(one.mandarin(), ["yī"], "Unihan's pinyin wins");
+		assert_eq!(one.hangul(), ["일"], "KANJIDIC2 fills what Unihan lacks");
+		assert_eq!(
+			one.meanings(),
+			["one", "one radical (no.1)"],
+			"KANJIDIC2's meanings win"
+		);
+		let xue = lookup.get('学');
+		assert_eq!(
+			xue.meanings(),
+			["learning, knowledge", "school"],
+			"senses of kDefinition"
+		);
+		assert_eq!(xue.hangul(), ["학"], "kHangul");
+		assert_eq!(
+			(xue.stroke_count(), xue.radical()),
+			(Some(8), Some(39)),
+			"strokes and radical from Unihan"
+		);
+	}
+}
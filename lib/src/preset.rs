@@ -0,0 +1,730 @@
+//! Lists of characters to test on, described by TOML or JSON definitions.
+//!
+//! A definition file holds a `preset` array. Each preset has a `name`, a `language`, an
+//! optional `description`, an optional `series` and `level` for graded lists such as the HSK,
+//! and a `source`, which is one of:
+//!
+//! - `{ type = "inline", characters = "一二三" }`
+//! - `{ type = "file", path = "list.txt" }`, relative to the definition file
+//! - `{ type = "query", grades = [1, 2], jlpt = [4], frequency = [1, 500], strokes = [1, 10],
+//!   radical = 9, readings = ["ja_kun"], skip = "1-*-*", order = "frequency" }`,
+//!   where every field is optional
+//! - `{ type = "unihan", list = "kTGH", range = [3501, 6500] }`, the characters of a list
+//!   Unihan records, see [`UnihanList`], with `range` picking positions in lists that number
+//!   their characters
+
+use std::{
+	collections::HashSet,
+	fs, io,
+	path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::{
+	extract::extract,
+	load_kanjidic::{Dict2, ReadingType},
+	query::{CharQuery, Order, SkipPattern},
+	unihan::Unihan,
+};
+
+const BUNDLED_DEFINITIONS: &str = include_str!("../presets/bundled.toml");
+
+/// Files that bundled `file` sources can refer to.
+const BUNDLED_FILES: &[(&str, &str)] = &[
+	(
+		"hanmun_hanja_1800.txt",
+		include_str!("../presets/hanmun_hanja_1800.txt"),
+	),
+	(
+		"common_cn_tier_1.txt",
+		include_str!("../presets/common_cn_tier_1.txt"),
+	),
+];
+
+crate::derive_str! {
+	#[ignore_case]
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	pub enum Language {
+		#[other]
+		Other(String),
+		Chinese = "zh" | "chinese",
+		Japanese = "ja" | "japanese",
+		Korean = "ko" | "korean",
+	}
+}
+
+impl Language {
+	/// The name of the language in that language.
+	#[must_use]
+	pub fn name(&self) -> &str {
+		match self {
+			Self::Chinese => "中文",
+			Self::Japanese => "日本語",
+			Self::Korean => "한국어",
+			Self::Other(s) => s,
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DefinitionFile {
+	#[serde(default)]
+	preset: Vec<Definition>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Definition {
+	name: String,
+	language: Language,
+	#[serde(default)]
+	description: String,
+	series: Option<String>,
+	level: Option<String>,
+	source: Source,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum Source {
+	Inline { characters: String },
+	File { path: PathBuf },
+	Query(QuerySpec),
+	Unihan(UnihanSpec),
+}
+
+/// The serialized form of a [`CharQuery`]. Ranges are inclusive `[min, max]` pairs.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct QuerySpec {
+	grades: Option<Vec<u64>>,
+	jlpt: Option<Vec<u64>>,
+	frequency: Option<[u64; 2]>,
+	strokes: Option<[u64; 2]>,
+	radical: Option<u64>,
+	readings: Vec<ReadingType>,
+	skip: Option<String>,
+	order: Order,
+}
+
+impl TryFrom<QuerySpec> for CharQuery {
+	type Error = String;
+
+	fn try_from(spec: QuerySpec) -> Result<Self, Self::Error> {
+		let mut query = Self::new().order(spec.order);
+		if let Some(grades) = spec.grades {
+			query = query.grades(grades);
+		}
+		if let Some(levels) = spec.jlpt {
+			query = query.jlpt(levels);
+		}
+		if let Some([min, max]) = spec.frequency {
+			query = query.frequency(min..=max);
+		}
+		if let Some([min, max]) = spec.strokes {
+			query = query.strokes(min..=max);
+		}
+		if let Some(radical) = spec.radical {
+			query = query.radical(radical);
+		}
+		for r_type in spec.readings {
+			query = query.has_reading(r_type);
+		}
+		if let Some(skip) = spec.skip {
+			query = query.skip(skip.parse::<SkipPattern>()?);
+		}
+		Ok(query)
+	}
+}
+
+crate::derive_str! {
+	/// The character lists Unihan records, by the field that marks them.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub enum UnihanList {
+		/// The 通用规范汉字表, numbered 1 to 8105 across its three tiers.
+		GeneralStandard = "kTGH",
+		/// The hanja for personal names, 인명용 한자.
+		KoreanName = "kKoreanName",
+	}
+}
+
+/// The serialized form of a list from Unihan. The range is an inclusive `[min, max]` pair.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UnihanSpec {
+	list: UnihanList,
+	range: Option<[u32; 2]>,
+}
+
+impl UnihanSpec {
+	/// The characters of the list in `unihan`, by position for numbered lists and by code
+	/// point otherwise.
+	fn run(&self, unihan: &Unihan) -> Vec<char> {
+		let mut positioned: Vec<(u32, char)> = unihan
+			.iter()
+			.filter_map(|(c, entry)| {
+				let position = match self.list {
+					UnihanList::GeneralStandard => entry.general_standard()?,
+					UnihanList::KoreanName => entry.korean_name().then_some(0)?,
+				};
+				self.range
+					.is_none_or(|[min, max]| (min..=max).contains(&position))
+					.then_some((position, c))
+			})
+			.collect();
+		positioned.sort_unstable();
+		positioned.into_iter().map(|(_, c)| c).collect()
+	}
+}
+
+/// Where a preset was defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+	Bundled,
+	File(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub struct Preset {
+	name: String,
+	language: Language,
+	description: String,
+	series: Option<String>,
+	level: Option<String>,
+	origin: Origin,
+	characters: Vec<char>,
+}
+
+impl Preset {
+	#[must_use]
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	#[must_use]
+	pub const fn language(&self) -> &Language {
+		&self.language
+	}
+
+	#[must_use]
+	pub fn description(&self) -> &str {
+		&self.description
+	}
+
+	/// The graded series this preset is one level of, like `HSK 3.0`.
+	#[must_use]
+	pub fn series(&self) -> Option<&str> {
+		self.series.as_deref()
+	}
+
+	#[must_use]
+	pub fn level(&self) -> Option<&str> {
+		self.level.as_deref()
+	}
+
+	#[must_use]
+	pub const fn origin(&self) -> &Origin {
+		&self.origin
+	}
+
+	#[must_use]
+	pub fn characters(&self) -> &[char] {
+		&self.characters
+	}
+
+	/// The characters as text, ready to be put into the input screen.
+	#[must_use]
+	pub fn text(&self) -> String {
+		self.characters.iter().collect()
+	}
+
+	/// Case-insensitive search over the name, description and language.
+	#[must_use]
+	pub fn matches(&self, needle: &str) -> bool {
+		let needle = needle.trim().to_lowercase();
+		[
+			self.name.as_str(),
+			&self.description,
+			self.language.as_str(),
+			self.language.name(),
+			self.series.as_deref().unwrap_or_default(),
+		]
+		.iter()
+		.any(|haystack| haystack.to_lowercase().contains(&needle))
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("Failed to read {}", path.display())]
+	Io { source: io::Error, path: PathBuf },
+	#[error("Failed to parse TOML")]
+	Toml(#[from] toml::de::Error),
+	#[error("Failed to parse JSON")]
+	Json(#[from] serde_json::Error),
+	#[error("Preset `{preset}` has an invalid query: {reason}")]
+	Query { preset: String, reason: String },
+	#[error("Preset `{preset}` needs {list} from Unihan, which isn't loaded")]
+	MissingUnihan { preset: String, list: UnihanList },
+}
+
+#[derive(Debug, Default)]
+pub struct PresetRegistry {
+	presets: Vec<Preset>,
+}
+
+impl PresetRegistry {
+	/// The bundled presets, followed by the ones defined in `*.toml` and `*.json` files in
+	/// `dir`, in file name order. Files that fail to load are logged and skipped, so one broken
+	/// file doesn't hide the others.
+	#[must_use]
+	pub fn load(dict: &Dict2, unihan: &Unihan, dir: &Path) -> Self {
+		let mut registry = Self::bundled(dict, unihan);
+		let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+			Ok(entries) => entries
+				.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+				.filter(|path| {
+					path.extension()
+						.is_some_and(|ext| ext == "toml" || ext == "json")
+				})
+				.collect(),
+			Err(e) => {
+				debug!("No user presets in {}: {e}", dir.display());
+				Vec::new()
+			}
+		};
+		paths.sort();
+		for path in paths {
+			if let Err(e) = registry.load_file(dict, unihan, &path) {
+				warn!("Skipping presets in {}: {e}", path.display());
+			}
+		}
+		registry
+	}
+
+	#[must_use]
+	#[allow(
+		clippy::missing_panics_doc,
+		reason = "The bundled definitions are checked by the tests"
+	)]
+	pub fn bundled(dict: &Dict2, unihan: &Unihan) -> Self {
+		let mut registry = Self::default();
+		let file: DefinitionFile =
+			toml::from_str(BUNDLED_DEFINITIONS).expect("bundled presets should parse");
+		for definition in file.preset {
+			let name = definition.name.clone();
+			match resolve(dict, unihan, definition, &Origin::Bundled, Path::new("")) {
+				Ok(preset) => registry.presets.push(preset),
+				Err(e @ Error::MissingUnihan { .. }) => debug!("Leaving out {name}: {e}"),
+				Err(e) => warn!("Leaving out {name}: {e}"),
+			}
+		}
+		registry
+	}
+
+	/// Adds every preset defined in the TOML or JSON file at `path`. Nothing is added if any
+	/// of them fails to load.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the file can't be read or parsed, or a preset's source can't be resolved.
+	pub fn load_file(&mut self, dict: &Dict2, unihan: &Unihan, path: &Path) -> Result<(), Error> {
+		let contents = fs::read_to_string(path).map_err(|source| Error::Io {
+			source,
+			path: path.to_owned(),
+		})?;
+		let file: DefinitionFile = if path.extension().is_some_and(|ext| ext == "json") {
+			serde_json::from_str(&contents)?
+		} else {
+			toml::from_str(&contents)?
+		};
+		let origin = Origin::File(path.to_owned());
+		let dir = path.parent().unwrap_or_else(|| Path::new(""));
+		let presets = file
+			.preset
+			.into_iter()
+			.map(|definition| resolve(dict, unihan, definition, &origin, dir))
+			.collect::<Result<Vec<_>, _>>()?;
+		debug!("Loaded {} presets from {}", presets.len(), path.display());
+		self.presets.extend(presets);
+		Ok(())
+	}
+
+	#[must_use]
+	pub fn presets(&self) -> &[Preset] {
+		&self.presets
+	}
+
+	/// Every series, in the order they were first defined.
+	#[must_use]
+	pub fn series(&self) -> Vec<&str> {
+		let mut seen = HashSet::new();
+		self.presets
+			.iter()
+			.filter_map(Preset::series)
+			.filter(|series| seen.insert(*series))
+			.collect()
+	}
+
+	/// How many characters of each level of `series` are in `known`.
+	#[must_use]
+	pub fn breakdown(&self, series: &str, known: &HashSet<char>) -> Vec<LevelCoverage<'_>> {
+		self.presets
+			.iter()
+			.filter(|preset| preset.series() == Some(series))
+			.map(|preset| LevelCoverage {
+				preset,
+				known: preset
+					.characters
+					.iter()
+					.filter(|c| known.contains(c))
+					.count(),
+			})
+			.collect()
+	}
+
+	pub fn search<'a>(&'a self, needle: &'a str) -> impl Iterator<Item = &'a Preset> {
+		self.presets
+			.iter()
+			.filter(move |preset| preset.matches(needle))
+	}
+}
+
+/// Tallied by [`PresetRegistry::breakdown`].
+#[derive(Debug, Clone, Copy)]
+pub struct LevelCoverage<'a> {
+	preset: &'a Preset,
+	known: usize,
+}
+
+impl<'a> LevelCoverage<'a> {
+	#[must_use]
+	pub const fn preset(&self) -> &'a Preset {
+		self.preset
+	}
+
+	#[must_use]
+	pub const fn known(&self) -> usize {
+		self.known
+	}
+
+	#[must_use]
+	pub const fn total(&self) -> usize {
+		self.preset.characters.len()
+	}
+}
+
+/// Relative `file` paths are resolved against `dir`, except for bundled presets, whose files
+/// are compiled in.
+fn resolve(
+	dict: &Dict2,
+	unihan: &Unihan,
+	definition: Definition,
+	origin: &Origin,
+	dir: &Path,
+) -> Result<Preset, Error> {
+	let characters = match definition.source {
+		Source::Inline { characters } => extract(&characters).into_chars(),
+		Source::File { path } => {
+			let text = if *origin == Origin::Bundled {
+				BUNDLED_FILES
+					.iter()
+					.find(|(name, _)| Path::new(name) == path)
+					.map(|(_, text)| (*text).to_owned())
+					.ok_or_else(|| Error::Io {
+						source: io::ErrorKind::NotFound.into(),
+						path,
+					})?
+			} else {
+				let path = dir.join(path);
+				fs::read_to_string(&path).map_err(|source| Error::Io { source, path })?
+			};
+			extract(&text).into_chars()
+		}
+		Source::Query(spec) => CharQuery::try_from(spec)
+			.map_err(|reason| Error::Query {
+				preset: definition.name.clone(),
+				reason,
+			})?
+			.run(dict),
+		Source::Unihan(spec) => {
+			let characters = spec.run(unihan);
+			if characters.is_empty() {
+				return Err(Error::MissingUnihan {
+					preset: definition.name,
+					list: spec.list,
+				});
+			}
+			characters
+		}
+	};
+	Ok(Preset {
+		name: definition.name,
+		language: definition.language,
+		description: definition.description,
+		series: definition.series,
+		level: definition.level,
+		origin: origin.clone(),
+		characters,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::load_kanjidic::fixture;
+
+	const UNIHAN: &str = "\
+U+4E00\tkTGH\t2013:1
+U+4E42\tkTGH\t2013:3502
+U+4E2B\tkTGH\t2013:3501
+U+4E2E\tkTGH\t2013:6501
+U+4F73\tkKoreanName\t2007
+U+4F73\tkMandarin\tjiā
+";
+
+	fn unihan() -> Unihan {
+		let mut unihan = Unihan::new();
+		unihan.read(UNIHAN.as_bytes()).expect("valid data");
+		unihan
+	}
+
+	fn bundled() -> PresetRegistry {
+		PresetRegistry::bundled(&fixture(), &unihan())
+	}
+
+	fn count(registry: &PresetRegistry, name: &str) -> usize {
+		registry
+			.presets()
+			.iter()
+			.find(|preset| preset.name() == name)
+			.map_or_else(
+				|| panic!("no preset named {name}"),
+				|preset| preset.characters().len(),
+			)
+	}
+
+	#[test]
+	fn bundled_presets_resolve() {
+		let registry = bundled();
+		assert_eq!(
+			count(&registry, "한문 교육용 기초 한자 1800"),
+			1800,
+			"bundled file"
+		);
+		assert_eq!(
+			count(&registry, "通用规范汉字表: 一级字表"),
+			3500,
+			"bundled file"
+		);
+		assert_eq!(count(&registry, "日本語能力試験"), 8, "query");
+		assert_eq!(count(&registry, "日本語能力試験 4級"), 2, "one JLPT level");
+		assert_eq!(count(&registry, "日本語能力試験 1級"), 1, "one JLPT level");
+		assert_eq!(count(&registry, "教育漢字: 1年"), 2, "one school year");
+		assert_eq!(count(&registry, "常用漢字: 中学校"), 1, "secondary school");
+		assert_eq!(count(&registry, "常用漢字"), 8, "query");
+		assert_eq!(count(&registry, "人名用漢字"), 2, "query");
+		let tier_2 = registry
+			.presets()
+			.iter()
+			.find(|preset| preset.name() == "通用规范汉字表: 二级字表")
+			.expect("tier 2 is bundled");
+		assert_eq!(tier_2.characters(), ['丫', '乂'], "Unihan list by position");
+		assert_eq!(
+			count(&registry, "通用规范汉字表: 三级字表"),
+			1,
+			"Unihan list"
+		);
+		assert_eq!(count(&registry, "인명용 한자"), 1, "Unihan list");
+		let levels: Vec<_> = registry
+			.breakdown("通用规范汉字表", &HashSet::new())
+			.iter()
+			.map(|level| level.preset().level())
+			.collect();
+		assert_eq!(
+			levels,
+			[Some("一级"), Some("二级"), Some("三级")],
+			"every tier is a level"
+		);
+	}
+
+	#[test]
+	fn unihan_presets_need_unihan() {
+		let registry = PresetRegistry::bundled(&fixture(), &Unihan::new());
+		assert!(
+			registry
+				.presets()
+				.iter()
+				.all(|preset| preset.name() != "인명용 한자"),
+			"left out without Unihan"
+		);
+		assert_eq!(
+			registry.presets().len() + 3,
+			bundled().presets().len(),
+			"only the Unihan lists are left out"
+		);
+	}
+
+	#[test]
+	fn every_bundled_definition_resolves() {
+		let file: DefinitionFile =
+			toml::from_str(BUNDLED_DEFINITIONS).expect("bundled presets should parse");
+		for definition in &file.preset {
+			if let Source::File { path } = &definition.source {
+				assert!(
+					BUNDLED_FILES
+						.iter()
+						.any(|(name, _)| Path::new(name) == path),
+					"{} of {} isn't bundled",
+					path.display(),
+					definition.name
+				);
+			}
+		}
+		assert_eq!(
+			bundled().presets().len(),
+			file.preset.len(),
+			"no bundled preset is left out"
+		);
+	}
+
+	#[test]
+	fn user_presets_load_from_toml_and_json() {
+		let dir = std::env::temp_dir().join(format!("hantracker-presets-{}", std::process::id()));
+		fs::create_dir_all(dir.join("lists")).expect("temp dir should be writable");
+		fs::write(dir.join("lists/week1.txt"), "一、二、三").expect("temp dir should be writable");
+		fs::write(
+			dir.join("course.toml"),
+			r#"
+			[[preset]]
+			name = "Week 1"
+			language = "ja"
+			source = { type = "file", path = "lists/week1.txt" }
+
+			[[preset]]
+			name = "Week 2"
+			language = "ja"
+			description = "Common radical 61"
+			source = { type = "query", radical = 61, order = "frequency" }
+			"#,
+		)
+		.expect("temp dir should be writable");
+		fs::write(
+			dir.join("extra.json"),
+			r#"{"preset": [{"name": "Numbers", "language": "zh", "source": {"type": "inline", "characters": "四 五"}}]}"#,
+		)
+		.expect("temp dir should be writable");
+		fs::write(dir.join("broken.toml"), "preset = 3").expect("temp dir should be writable");
+
+		let registry = PresetRegistry::load(&fixture(), &unihan(), &dir);
+		fs::remove_dir_all(&dir).expect("temp dir should be removable");
+
+		let bundled = bundled().presets().len();
+		assert_eq!(
+			registry.presets().len(),
+			bundled + 3,
+			"broken files are skipped"
+		);
+		assert_eq!(
+			registry.presets()[bundled].name(),
+			"Week 1",
+			"files load in name order"
+		);
+		assert_eq!(count(&registry, "Week 1"), 3, "file sources are relative");
+		assert_eq!(
+			registry.presets()[bundled + 1].characters(),
+			['悪', '愛'],
+			"query sources keep their order"
+		);
+		assert_eq!(
+			registry.presets()[bundled + 1].origin(),
+			&Origin::File(dir.join("course.toml")),
+			"origin is the definition file"
+		);
+		assert_eq!(count(&registry, "Numbers"), 2, "inline sources");
+	}
+
+	#[test]
+	fn invalid_query_is_an_error() {
+		let mut registry = PresetRegistry::default();
+		let dir = std::env::temp_dir().join(format!("hantracker-bad-query-{}", std::process::id()));
+		fs::create_dir_all(&dir).expect("temp dir should be writable");
+		let path = dir.join("bad.toml");
+		fs::write(
+			&path,
+			r#"preset = [{ name = "Bad", language = "ja", source = { type = "query", skip = "9" } }]"#,
+		)
+		.expect("temp dir should be writable");
+		let result = registry.load_file(&fixture(), &unihan(), &path);
+		fs::remove_dir_all(&dir).expect("temp dir should be removable");
+		assert!(
+			matches!(result, Err(Error::Query { .. })),
+			"unexpected result: {result:?}"
+		);
+		assert!(registry.presets().is_empty(), "nothing is added");
+	}
+
+	#[test]
+	fn graded_user_presets_and_breakdown() {
+		let dir = std::env::temp_dir().join(format!("hantracker-graded-{}", std::process::id()));
+		fs::create_dir_all(dir.join("hsk30")).expect("temp dir should be writable");
+		fs::write(dir.join("hsk30/1.txt"), "一\n二\n三\n").expect("temp dir should be writable");
+		fs::write(
+			dir.join("hsk.toml"),
+			r#"
+				[[preset]]
+				name = "HSK 3.0: 1级"
+				language = "zh"
+				series = "HSK 3.0"
+				level = "1"
+				source = { type = "file", path = "hsk30/1.txt" }
+
+				[[preset]]
+				name = "HSK 3.0: 2级"
+				language = "zh"
+				series = "HSK 3.0"
+				level = "2"
+				source = { type = "inline", characters = "四五" }
+			"#,
+		)
+		.expect("temp dir should be writable");
+		let registry = PresetRegistry::load(&fixture(), &unihan(), &dir);
+		fs::remove_dir_all(&dir).expect("temp dir should be removable");
+
+		assert_eq!(
+			count(&registry, "HSK 3.0: 1级"),
+			3,
+			"list next to the definition"
+		);
+		assert_eq!(
+			registry.series(),
+			["通用规范汉字表", "HSK 3.0"],
+			"series in definition order"
+		);
+		let known = HashSet::from(['一', '三', '五', '的']);
+		let breakdown: Vec<_> = registry
+			.breakdown("HSK 3.0", &known)
+			.iter()
+			.map(|level| (level.preset().level(), level.known(), level.total()))
+			.collect();
+		assert_eq!(
+			breakdown,
+			[(Some("1"), 2, 3), (Some("2"), 1, 2)],
+			"known characters per level"
+		);
+	}
+
+	#[test]
+	fn search() {
+		let registry = bundled();
+		let names: Vec<&str> = registry.search("教育").map(Preset::name).collect();
+		assert_eq!(names.len(), 7, "all and each grade: {names:?}");
+		assert_eq!(registry.search("ZH").count(), 3, "language codes");
+		assert_eq!(registry.search("한국어").count(), 2, "language names");
+		assert_eq!(registry.search("jlpt").count(), 3, "descriptions");
+	}
+}
@@ -0,0 +1,280 @@
+use std::{
+	collections::HashSet,
+	fmt,
+	ops::{Bound, RangeBounds},
+	str::FromStr,
+};
+
+use crate::{
+	load_kanjidic::{Character, Dict2, QueryCodeType, RadicalType, ReadingType},
+	vec_string_to_vec_char,
+};
+
+crate::derive_str! {
+	#[ignore_case]
+	/// The order [`CharQuery::run`] returns characters in.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+	pub enum Order {
+		/// By Unicode code point.
+		#[default]
+		Codepoint = "codepoint",
+		/// Most frequent first. Characters without a frequency rank come last.
+		Frequency = "frequency" | "freq",
+		/// Fewest strokes first.
+		Strokes = "strokes",
+		/// Lowest school grade first, then by frequency.
+		Grade = "grade",
+		/// The order they appear in the dictionary file.
+		Dictionary = "dictionary",
+	}
+}
+
+/// A set of filters over the dictionary. Every filter that is set has to match.
+///
+/// ```
+/// # use lib::query::{CharQuery, Order};
+/// let query = CharQuery::new().grades([1, 2]).frequency(..=500).order(Order::Frequency);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CharQuery {
+	grades: Option<HashSet<u64>>,
+	jlpt: Option<HashSet<u64>>,
+	frequency: Option<(Bound<u64>, Bound<u64>)>,
+	strokes: Option<(Bound<u64>, Bound<u64>)>,
+	radical: Option<u64>,
+	reading_types: Vec<ReadingType>,
+	skip: Option<SkipPattern>,
+	order: Order,
+}
+
+impl CharQuery {
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Keeps characters taught in any of `grades`, as numbered in KANJIDIC2.
+	#[must_use]
+	pub fn grades(mut self, grades: impl IntoIterator<Item = u64>) -> Self {
+		self.grades = Some(grades.into_iter().collect());
+		self
+	}
+
+	/// Keeps characters in any of the old (pre-2010) JLPT `levels`.
+	#[must_use]
+	pub fn jlpt(mut self, levels: impl IntoIterator<Item = u64>) -> Self {
+		self.jlpt = Some(levels.into_iter().collect());
+		self
+	}
+
+	/// Keeps characters whose newspaper frequency rank is within `ranks`.
+	#[must_use]
+	pub fn frequency(mut self, ranks: impl RangeBounds<u64>) -> Self {
+		self.frequency = Some(to_bounds(&ranks));
+		self
+	}
+
+	#[must_use]
+	pub fn strokes(mut self, strokes: impl RangeBounds<u64>) -> Self {
+		self.strokes = Some(to_bounds(&strokes));
+		self
+	}
+
+	/// Keeps characters filed under the classical (Kangxi) radical `number`.
+	#[must_use]
+	pub const fn radical(mut self, number: u64) -> Self {
+		self.radical = Some(number);
+		self
+	}
+
+	/// Keeps characters with at least one reading of `r_type`. Can be called more than once.
+	#[must_use]
+	pub fn has_reading(mut self, r_type: ReadingType) -> Self {
+		self.reading_types.push(r_type);
+		self
+	}
+
+	#[must_use]
+	pub const fn skip(mut self, pattern: SkipPattern) -> Self {
+		self.skip = Some(pattern);
+		self
+	}
+
+	#[must_use]
+	pub const fn order(mut self, order: Order) -> Self {
+		self.order = order;
+		self
+	}
+
+	#[must_use]
+	pub fn matches(&self, c: &Character) -> bool {
+		let in_set = |set: &Option<HashSet<u64>>, value: Option<u64>| {
+			set.as_ref()
+				.is_none_or(|set| value.is_some_and(|v| set.contains(&v)))
+		};
+		let in_range = |range: &Option<(Bound<u64>, Bound<u64>)>, value: Option<u64>| {
+			range
+				.as_ref()
+				.is_none_or(|range| value.is_some_and(|v| range.contains(&v)))
+		};
+		in_set(&self.grades, c.grade())
+			&& in_set(&self.jlpt, c.jlpt())
+			&& in_range(&self.frequency, c.freq())
+			&& in_range(&self.strokes, c.stroke_count())
+			&& self
+				.radical
+				.is_none_or(|r| c.radical(&RadicalType::Classical) == Some(r))
+			&& self
+				.reading_types
+				.iter()
+				.all(|r_type| c.readings().any(|r| r.r_type() == r_type))
+			&& self.skip.as_ref().is_none_or(|pattern| {
+				c.query_codes()
+					.any(|q| q.qc_type() == &QueryCodeType::Skip && pattern.matches(q.value()))
+			})
+	}
+
+	#[must_use]
+	#[allow(
+		clippy::missing_panics_doc,
+		reason = "I'm guaranteeing all strings are single characters"
+	)]
+	pub fn run(&self, dict: &Dict2) -> Vec<char> {
+		let mut chars: Vec<&Character> = dict
+			.characters()
+			.iter()
+			.filter(|c| self.matches(c))
+			.collect();
+		match self.order {
+			Order::Codepoint => chars.sort_by(|a, b| a.literal().cmp(b.literal())),
+			Order::Frequency => chars.sort_by_key(|c| (c.freq().unwrap_or(u64::MAX), c.literal())),
+			Order::Strokes => {
+				chars.sort_by_key(|c| (c.stroke_count().unwrap_or(u64::MAX), c.literal()));
+			}
+			Order::Grade => chars.sort_by_key(|c| {
+				(
+					c.grade().unwrap_or(u64::MAX),
+					c.freq().unwrap_or(u64::MAX),
+					c.literal(),
+				)
+			}),
+			Order::Dictionary => {}
+		}
+		let chars = chars.into_iter().map(|c| c.literal().to_owned()).collect();
+		vec_string_to_vec_char(chars).expect("all strings were not single characters")
+	}
+}
+
+fn to_bounds(range: &impl RangeBounds<u64>) -> (Bound<u64>, Bound<u64>) {
+	(range.start_bound().cloned(), range.end_bound().cloned())
+}
+
+/// A SKIP code where any of the three numbers may be left open, written like `1-4-*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkipPattern([Option<u64>; 3]);
+
+impl SkipPattern {
+	/// Also matches the misclassification codes KANJIDIC2 lists, since those are the codes
+	/// people are likely to come up with when looking a character up.
+	#[must_use]
+	pub fn matches(&self, code: &str) -> bool {
+		let mut parts = code.split('-').map(|part| part.parse::<u64>().ok());
+		self.0
+			.iter()
+			.all(|expected| match (expected, parts.next().flatten()) {
+				(None, Some(_)) => true,
+				(Some(expected), Some(part)) => *expected == part,
+				(_, None) => false,
+			})
+			&& parts.next().is_none()
+	}
+}
+
+impl FromStr for SkipPattern {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut pattern = [None; 3];
+		let mut parts = s.trim().split('-');
+		for slot in &mut pattern {
+			*slot = match parts.next().map(str::trim) {
+				Some("*" | "") => None,
+				Some(part) => Some(part.parse().map_err(|_| format!("bad SKIP pattern: {s}"))?),
+				None => return Err(format!("bad SKIP pattern: {s}")),
+			};
+		}
+		if parts.next().is_some() {
+			return Err(format!("bad SKIP pattern: {s}"));
+		}
+		Ok(Self(pattern))
+	}
+}
+
+impl fmt::Display for SkipPattern {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let parts: Vec<String> = self
+			.0
+			.iter()
+			.map(|part| part.map_or_else(|| "*".to_owned(), |n| n.to_string()))
+			.collect();
+		f.write_str(&parts.join("-"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::load_kanjidic::fixture;
+
+	fn run(query: &CharQuery) -> String {
+		query.run(&fixture()).into_iter().collect()
+	}
+
+	#[test]
+	fn empty_query_matches_everything() {
+		assert_eq!(
+			run(&CharQuery::new()),
+			"一丂丑亙亜右圧引悪愛異",
+			"sorted by code point"
+		);
+	}
+
+	#[test]
+	fn filters_combine() {
+		let query = CharQuery::new()
+			.grades([1, 2, 3])
+			.strokes(4..=11)
+			.has_reading(ReadingType::Pinyin);
+		assert_eq!(run(&query), "右引悪", "every filter has to match");
+		assert_eq!(
+			run(&CharQuery::new().radical(61)),
+			"悪愛",
+			"classical radical"
+		);
+		assert_eq!(run(&CharQuery::new().jlpt([1])), "亜", "JLPT level");
+	}
+
+	#[test]
+	fn orders() {
+		let query = CharQuery::new().frequency(..=700);
+		assert_eq!(
+			run(&query.clone().order(Order::Frequency)),
+			"一引悪右異愛",
+			"most frequent first"
+		);
+		assert_eq!(
+			run(&query.order(Order::Strokes)),
+			"一引右悪異愛",
+			"fewest strokes first"
+		);
+	}
+
+	#[test]
+	fn skip_patterns() {
+		let pattern: SkipPattern = "3-2-*".parse().expect("valid pattern");
+		assert_eq!(pattern.to_string(), "3-2-*", "round-trips");
+		assert_eq!(run(&CharQuery::new().skip(pattern)), "右圧", "wildcard");
+		assert!("3-2".parse::<SkipPattern>().is_err(), "too short");
+		assert!("3-x-1".parse::<SkipPattern>().is_err(), "not a number");
+	}
+}
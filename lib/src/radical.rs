@@ -0,0 +1,171 @@
+//! The characters of KANJIDIC2 grouped by the radical they're filed under.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{
+	load_kanjidic::{Dict2, RadicalType},
+	script::kangxi_radical,
+};
+
+/// The characters filed under one radical.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadicalGroup {
+	number: u64,
+	radical: Option<char>,
+	names: Vec<String>,
+	characters: Vec<char>,
+}
+
+impl RadicalGroup {
+	#[must_use]
+	pub const fn number(&self) -> u64 {
+		self.number
+	}
+
+	/// The radical itself. Only known for the classical numbering, which is the Kangxi one.
+	#[must_use]
+	pub const fn radical(&self) -> Option<char> {
+		self.radical
+	}
+
+	/// Japanese names of the radical, like いち.
+	#[must_use]
+	pub fn names(&self) -> &[String] {
+		&self.names
+	}
+
+	/// By stroke count, then in dictionary order.
+	#[must_use]
+	pub fn characters(&self) -> &[char] {
+		&self.characters
+	}
+
+	/// How many of the characters are `known`.
+	#[must_use]
+	pub fn known(&self, known: &HashSet<char>) -> usize {
+		self.characters.iter().filter(|c| known.contains(c)).count()
+	}
+}
+
+/// Every radical with characters filed under it, by number.
+#[derive(Debug, Clone, Default)]
+pub struct RadicalIndex {
+	groups: Vec<RadicalGroup>,
+	by_char: HashMap<char, u64>,
+}
+
+impl RadicalIndex {
+	#[must_use]
+	pub fn new(dict: &Dict2, rad_type: &RadicalType) -> Self {
+		let mut groups: BTreeMap<u64, RadicalGroup> = BTreeMap::new();
+		let mut strokes = HashMap::new();
+		let mut by_char = HashMap::new();
+		for character in dict.characters() {
+			let (Some(c), Some(number)) = (
+				character.literal().chars().next(),
+				character.radical(rad_type),
+			) else {
+				continue;
+			};
+			let group = groups.entry(number).or_insert_with(|| RadicalGroup {
+				number,
+				radical: (rad_type == &RadicalType::Classical)
+					.then(|| kangxi_radical(number))
+					.flatten(),
+				names: Vec::new(),
+				characters: Vec::new(),
+			});
+			for name in character.radical_names() {
+				if !group.names.contains(name) {
+					group.names.push(name.clone());
+				}
+			}
+			group.characters.push(c);
+			strokes.insert(c, character.stroke_count().unwrap_or(u64::MAX));
+			by_char.insert(c, number);
+		}
+		let mut groups: Vec<RadicalGroup> = groups.into_values().collect();
+		for group in &mut groups {
+			group.characters.sort_by_key(|c| strokes[c]);
+		}
+		Self { groups, by_char }
+	}
+
+	#[must_use]
+	pub fn groups(&self) -> &[RadicalGroup] {
+		&self.groups
+	}
+
+	#[must_use]
+	pub fn group(&self, number: u64) -> Option<&RadicalGroup> {
+		self.groups
+			.binary_search_by_key(&number, RadicalGroup::number)
+			.ok()
+			.map(|index| &self.groups[index])
+	}
+
+	/// The number of the radical `c` is filed under.
+	#[must_use]
+	pub fn radical_of(&self, c: char) -> Option<u64> {
+		self.by_char.get(&c).copied()
+	}
+
+	/// Puts characters with the same radical next to each other, by radical number, keeping
+	/// their order within a radical. Characters that aren't in the dictionary go last.
+	pub fn group_by_radical(&self, chars: &mut [char]) {
+		chars.sort_by_key(|c| self.radical_of(*c).unwrap_or(u64::MAX));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::load_kanjidic::fixture;
+
+	#[test]
+	fn groups_by_classical_radical() {
+		let dict = fixture();
+		let index = RadicalIndex::new(&dict, &RadicalType::Classical);
+		let one = index.group(1).expect("radical 1 has characters");
+		assert_eq!(one.radical(), Some('一'), "the Kangxi radical");
+		assert_eq!(one.names(), ["いち"], "from the radical's own entry");
+		assert_eq!(one.characters(), ['一', '丂', '丑'], "by stroke count");
+		assert_eq!(
+			one.known(&HashSet::from(['一', '右'])),
+			1,
+			"only the group's characters count"
+		);
+		assert!(
+			index
+				.groups()
+				.windows(2)
+				.all(|pair| pair[0].number() < pair[1].number()),
+			"by radical number"
+		);
+
+		let nelson = RadicalIndex::new(&dict, &RadicalType::NelsonC);
+		assert_eq!(
+			nelson.group(1).map(RadicalGroup::characters),
+			Some(['一', '亜'].as_slice()),
+			"Nelson files 亜 under 一"
+		);
+		assert_eq!(
+			nelson.group(1).and_then(RadicalGroup::radical),
+			None,
+			"not Kangxi"
+		);
+	}
+
+	#[test]
+	fn groups_a_test_by_radical() {
+		let dict = fixture();
+		let index = RadicalIndex::new(&dict, &RadicalType::Classical);
+		let mut chars = ['愛', '学', '丑', '悪', '一'];
+		index.group_by_radical(&mut chars);
+		assert_eq!(
+			chars,
+			['丑', '一', '愛', '悪', '学'],
+			"same radical together, in their order"
+		);
+	}
+}
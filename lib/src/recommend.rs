@@ -0,0 +1,303 @@
+//! Which characters to learn next, by how useful and how easy to learn they are.
+
+use std::collections::HashSet;
+
+use crate::{
+	coverage::ratio,
+	ids::Ids,
+	load_kanjidic::{Character, Dict2, RadicalType, MAX_FREQ_RANK},
+	script::kangxi_radical,
+	unihan::{self, Unihan},
+};
+
+/// How much each signal counts towards the score. Signals are all from 0 to 1, so the weights
+/// say directly how much one signal is worth against another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weights {
+	pub frequency: f64,
+	pub grade: f64,
+	pub components: f64,
+	pub phonetic: f64,
+}
+
+impl Default for Weights {
+	fn default() -> Self {
+		Self {
+			frequency: 1.0,
+			grade: 0.5,
+			components: 0.25,
+			phonetic: 0.25,
+		}
+	}
+}
+
+/// What the score of a character is made of, each from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Signals {
+	/// How common the character is: its newspaper rank in KANJIDIC2, or Unihan's frequency.
+	pub frequency: f64,
+	/// How early the character is taught: its Japanese school grade, or the Hong Kong one.
+	pub grade: f64,
+	/// The share of the character's components that are known, or without IDS data, whether
+	/// the radical it's filed under is known.
+	pub components: f64,
+	/// Whether another character of the same phonetic series is known.
+	pub phonetic: f64,
+}
+
+impl Signals {
+	#[must_use]
+	pub fn score(&self, weights: &Weights) -> f64 {
+		let score = self.frequency * weights.frequency;
+		let score = self.grade.mul_add(weights.grade, score);
+		let score = self.components.mul_add(weights.components, score);
+		self.phonetic.mul_add(weights.phonetic, score)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Recommendation {
+	char: char,
+	signals: Signals,
+	score: f64,
+}
+
+impl Recommendation {
+	#[must_use]
+	pub const fn char(&self) -> char {
+		self.char
+	}
+
+	#[must_use]
+	pub const fn signals(&self) -> &Signals {
+		&self.signals
+	}
+
+	#[must_use]
+	pub const fn score(&self) -> f64 {
+		self.score
+	}
+}
+
+/// Ranks characters to learn with data from KANJIDIC2 and, when they're loaded, Unihan and
+/// IDS.
+#[derive(Debug, Clone, Copy)]
+pub struct Recommender<'a> {
+	dict: &'a Dict2,
+	unihan: Option<&'a Unihan>,
+	ids: Option<&'a Ids>,
+	weights: Weights,
+}
+
+impl<'a> Recommender<'a> {
+	#[must_use]
+	pub fn new(dict: &'a Dict2) -> Self {
+		Self {
+			dict,
+			unihan: None,
+			ids: None,
+			weights: Weights::default(),
+		}
+	}
+
+	#[must_use]
+	pub const fn with_unihan(mut self, unihan: &'a Unihan) -> Self {
+		self.unihan = Some(unihan);
+		self
+	}
+
+	#[must_use]
+	pub const fn with_ids(mut self, ids: &'a Ids) -> Self {
+		self.ids = Some(ids);
+		self
+	}
+
+	#[must_use]
+	pub const fn with_weights(mut self, weights: Weights) -> Self {
+		self.weights = weights;
+		self
+	}
+
+	/// The `n` best of `candidates` that aren't `known`, best first. Characters with the same
+	/// score keep the order of `candidates`.
+	#[must_use]
+	pub fn recommend(
+		&self,
+		candidates: impl IntoIterator<Item = char>,
+		known: &HashSet<char>,
+		n: usize,
+	) -> Vec<Recommendation> {
+		let known_series: HashSet<u32> = self.unihan.map_or_else(HashSet::new, |unihan| {
+			known
+				.iter()
+				.filter_map(|c| unihan.get(*c))
+				.flat_map(|entry| entry.phonetic_series().iter().copied())
+				.collect()
+		});
+		let mut seen = HashSet::new();
+		let mut recommendations: Vec<Recommendation> = candidates
+			.into_iter()
+			.filter(|c| !known.contains(c) && seen.insert(*c))
+			.map(|c| {
+				let signals = self.signals(c, known, &known_series);
+				Recommendation {
+					char: c,
+					signals,
+					score: signals.score(&self.weights),
+				}
+			})
+			.collect();
+		recommendations.sort_by(|a, b| b.score.total_cmp(&a.score));
+		recommendations.truncate(n);
+		recommendations
+	}
+
+	fn signals(&self, c: char, known: &HashSet<char>, known_series: &HashSet<u32>) -> Signals {
+		let kanjidic = self.dict.get(c);
+		let unihan = self.unihan.and_then(|unihan| unihan.get(c));
+		let frequency = kanjidic.and_then(Character::freq).map_or_else(
+			|| {
+				unihan
+					.and_then(unihan::Entry::frequency)
+					.map_or(0.0, |frequency| scale(frequency, 5))
+			},
+			|rank| scale(rank, MAX_FREQ_RANK),
+		);
+		let grade = kanjidic
+			.and_then(Character::grade)
+			.or_else(|| unihan?.grade_level())
+			.map_or(0.0, grade_signal);
+		let components = self
+			.ids
+			.map(|ids| ids.components(c))
+			.filter(|components| !components.is_empty())
+			.map_or_else(
+				|| {
+					let radical = kanjidic
+						.and_then(|character| character.radical(&RadicalType::Classical))
+						.or_else(|| Some(unihan?.radical_strokes().first()?.radical()))
+						.and_then(kangxi_radical);
+					match radical {
+						Some(radical) if radical != c && known.contains(&radical) => 1.0,
+						_ => 0.0,
+					}
+				},
+				|components| {
+					let known_components = components.iter().filter(|c| known.contains(c)).count();
+					ratio(known_components, components.len())
+				},
+			);
+		let in_known_series = unihan.is_some_and(|entry| {
+			entry
+				.phonetic_series()
+				.iter()
+				.any(|series| known_series.contains(series))
+		});
+		Signals {
+			frequency,
+			grade,
+			components,
+			phonetic: if in_known_series { 1.0 } else { 0.0 },
+		}
+	}
+}
+
+/// 1 for the first of `count` ranks, down to almost 0 for the last.
+#[allow(
+	clippy::as_conversions,
+	clippy::cast_precision_loss,
+	reason = "Ranks stay far below the point where an f64 loses precision"
+)]
+fn scale(rank: u64, count: u64) -> f64 {
+	let rank = rank.clamp(1, count);
+	(count - rank + 1) as f64 / count as f64
+}
+
+/// Lower grades score higher. KANJIDIC2's grade 8 is the rest of the jōyō kanji, and 9 and
+/// 10 are for names.
+fn grade_signal(grade: u64) -> f64 {
+	match grade {
+		1..=6 => scale(grade, 8),
+		8 => 0.25,
+		9 | 10 => 0.125,
+		_ => 0.0,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::load_kanjidic::fixture;
+
+	fn chars(recommendations: &[Recommendation]) -> String {
+		recommendations.iter().map(Recommendation::char).collect()
+	}
+
+	#[test]
+	fn ranks_by_frequency_and_grade() {
+		let dict = fixture();
+		let known = HashSet::from(['一']);
+		let candidates = dict
+			.characters()
+			.iter()
+			.filter_map(|c| c.literal().chars().next());
+		let by_frequency = Recommender::new(&dict).with_weights(Weights {
+			frequency: 1.0,
+			grade: 0.0,
+			components: 0.0,
+			phonetic: 0.0,
+		});
+		assert_eq!(
+			chars(&by_frequency.recommend(candidates.clone(), &known, 4)),
+			"引悪右異",
+			"newspaper rank, without known characters"
+		);
+		let by_grade = by_frequency.with_weights(Weights {
+			frequency: 0.0,
+			grade: 1.0,
+			components: 0.0,
+			phonetic: 0.0,
+		});
+		assert_eq!(
+			chars(&by_grade.recommend(candidates, &known, 3)),
+			"右引悪",
+			"lower grades first"
+		);
+	}
+
+	#[test]
+	fn known_components_and_phonetic_series() {
+		let dict = fixture();
+		let mut unihan = Unihan::new();
+		unihan
+			.read("U+611B\tkPhonetic\t2\nU+60AA\tkPhonetic\t2\n".as_bytes())
+			.expect("valid data");
+		let recommender = Recommender::new(&dict)
+			.with_unihan(&unihan)
+			.with_weights(Weights {
+				frequency: 0.0,
+				grade: 0.0,
+				components: 1.0,
+				phonetic: 0.5,
+			});
+		let known = HashSet::from(['一', '悪']);
+		let recommendations = recommender.recommend("亙丂丑愛右".chars(), &known, 3);
+		assert_eq!(
+			chars(&recommendations),
+			"丂丑愛",
+			"radical 一 known, then 愛 in the series of 悪"
+		);
+		assert!(
+			(recommendations[2].signals().phonetic - 1.0).abs() < f64::EPSILON,
+			"phonetic signal"
+		);
+
+		let ids = Ids::from_reader("U+611B\t愛\t⿱⿱爫冖⿱心夂\n".as_bytes()).expect("valid data");
+		let known = HashSet::from(['心', '冖']);
+		let recommendation = recommender.with_ids(&ids).recommend(['愛'], &known, 1)[0];
+		assert!(
+			(recommendation.signals().components - 0.5).abs() < f64::EPSILON,
+			"two of four components known"
+		);
+	}
+}
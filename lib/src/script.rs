@@ -0,0 +1,386 @@
+//! Which CJK block and script a character belongs to.
+//!
+//! The block ranges follow `data/Blocks.txt` from the Unicode Character Database at
+//! [`UNICODE_VERSION`]. [`BLOCKS`] is generated from that file: the tests rebuild the table from
+//! it and print the new one when the data has changed.
+
+use unicode_normalization::char::decompose_compatible;
+
+/// The version of the Unicode Character Database the block table follows.
+pub const UNICODE_VERSION: (u8, u8, u8) = (17, 0, 0);
+
+crate::derive_str! {
+	/// The Unicode blocks that hold CJK characters, named as in `Blocks.txt`.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum CjkBlock {
+		UnifiedIdeographs = "CJK Unified Ideographs",
+		ExtensionA = "CJK Unified Ideographs Extension A",
+		ExtensionB = "CJK Unified Ideographs Extension B",
+		ExtensionC = "CJK Unified Ideographs Extension C",
+		ExtensionD = "CJK Unified Ideographs Extension D",
+		ExtensionE = "CJK Unified Ideographs Extension E",
+		ExtensionF = "CJK Unified Ideographs Extension F",
+		ExtensionG = "CJK Unified Ideographs Extension G",
+		ExtensionH = "CJK Unified Ideographs Extension H",
+		ExtensionI = "CJK Unified Ideographs Extension I",
+		ExtensionJ = "CJK Unified Ideographs Extension J",
+		CompatibilityIdeographs = "CJK Compatibility Ideographs",
+		CompatibilityIdeographsSupplement = "CJK Compatibility Ideographs Supplement",
+		KangxiRadicals = "Kangxi Radicals",
+		RadicalsSupplement = "CJK Radicals Supplement",
+		Strokes = "CJK Strokes",
+		IdeographicDescriptionCharacters = "Ideographic Description Characters",
+		SymbolsAndPunctuation = "CJK Symbols and Punctuation",
+		IdeographicSymbolsAndPunctuation = "Ideographic Symbols and Punctuation",
+		EnclosedLettersAndMonths = "Enclosed CJK Letters and Months",
+		EnclosedIdeographicSupplement = "Enclosed Ideographic Supplement",
+		Compatibility = "CJK Compatibility",
+		CompatibilityForms = "CJK Compatibility Forms",
+		Kanbun = "Kanbun",
+		Hiragana = "Hiragana",
+		Katakana = "Katakana",
+		KatakanaPhoneticExtensions = "Katakana Phonetic Extensions",
+		KanaSupplement = "Kana Supplement",
+		KanaExtendedA = "Kana Extended-A",
+		KanaExtendedB = "Kana Extended-B",
+		SmallKanaExtension = "Small Kana Extension",
+		HangulSyllables = "Hangul Syllables",
+		HangulJamo = "Hangul Jamo",
+		HangulJamoExtendedA = "Hangul Jamo Extended-A",
+		HangulJamoExtendedB = "Hangul Jamo Extended-B",
+		HangulCompatibilityJamo = "Hangul Compatibility Jamo",
+		Bopomofo = "Bopomofo",
+		BopomofoExtended = "Bopomofo Extended",
+		/// Fullwidth ASCII, halfwidth katakana and halfwidth hangul.
+		HalfwidthAndFullwidthForms = "Halfwidth and Fullwidth Forms",
+		VerticalForms = "Vertical Forms",
+		SmallFormVariants = "Small Form Variants",
+		YijingHexagramSymbols = "Yijing Hexagram Symbols",
+		TaiXuanJingSymbols = "Tai Xuan Jing Symbols",
+		CountingRodNumerals = "Counting Rod Numerals",
+	}
+}
+
+crate::derive_str! {
+	#[ignore_case]
+	/// A coarser grouping of [`CjkBlock`]s. Blocks of symbols and punctuation shared by the
+	/// scripts are [`Script::Common`].
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum Script {
+		Han = "han" | "hanzi" | "kanji" | "hanja",
+		Kana = "kana",
+		Hangul = "hangul",
+		Bopomofo = "bopomofo",
+		Common = "common",
+	}
+}
+
+/// The first and last code point of every [`CjkBlock`], sorted. Generated from
+/// `data/Blocks.txt`, see the module documentation.
+pub const BLOCKS: &[(u32, u32, CjkBlock)] = &[
+	(0x1100, 0x11FF, CjkBlock::HangulJamo),
+	(0x2E80, 0x2EFF, CjkBlock::RadicalsSupplement),
+	(0x2F00, 0x2FDF, CjkBlock::KangxiRadicals),
+	(0x2FF0, 0x2FFF, CjkBlock::IdeographicDescriptionCharacters),
+	(0x3000, 0x303F, CjkBlock::SymbolsAndPunctuation),
+	(0x3040, 0x309F, CjkBlock::Hiragana),
+	(0x30A0, 0x30FF, CjkBlock::Katakana),
+	(0x3100, 0x312F, CjkBlock::Bopomofo),
+	(0x3130, 0x318F, CjkBlock::HangulCompatibilityJamo),
+	(0x3190, 0x319F, CjkBlock::Kanbun),
+	(0x31A0, 0x31BF, CjkBlock::BopomofoExtended),
+	(0x31C0, 0x31EF, CjkBlock::Strokes),
+	(0x31F0, 0x31FF, CjkBlock::KatakanaPhoneticExtensions),
+	(0x3200, 0x32FF, CjkBlock::EnclosedLettersAndMonths),
+	(0x3300, 0x33FF, CjkBlock::Compatibility),
+	(0x3400, 0x4DBF, CjkBlock::ExtensionA),
+	(0x4DC0, 0x4DFF, CjkBlock::YijingHexagramSymbols),
+	(0x4E00, 0x9FFF, CjkBlock::UnifiedIdeographs),
+	(0xA960, 0xA97F, CjkBlock::HangulJamoExtendedA),
+	(0xAC00, 0xD7AF, CjkBlock::HangulSyllables),
+	(0xD7B0, 0xD7FF, CjkBlock::HangulJamoExtendedB),
+	(0xF900, 0xFAFF, CjkBlock::CompatibilityIdeographs),
+	(0xFE10, 0xFE1F, CjkBlock::VerticalForms),
+	(0xFE30, 0xFE4F, CjkBlock::CompatibilityForms),
+	(0xFE50, 0xFE6F, CjkBlock::SmallFormVariants),
+	(0xFF00, 0xFFEF, CjkBlock::HalfwidthAndFullwidthForms),
+	(0x16FE0, 0x16FFF, CjkBlock::IdeographicSymbolsAndPunctuation),
+	(0x1AFF0, 0x1AFFF, CjkBlock::KanaExtendedB),
+	(0x1B000, 0x1B0FF, CjkBlock::KanaSupplement),
+	(0x1B100, 0x1B12F, CjkBlock::KanaExtendedA),
+	(0x1B130, 0x1B16F, CjkBlock::SmallKanaExtension),
+	(0x1D300, 0x1D35F, CjkBlock::TaiXuanJingSymbols),
+	(0x1D360, 0x1D37F, CjkBlock::CountingRodNumerals),
+	(0x1F200, 0x1F2FF, CjkBlock::EnclosedIdeographicSupplement),
+	(0x20000, 0x2A6DF, CjkBlock::ExtensionB),
+	(0x2A700, 0x2B73F, CjkBlock::ExtensionC),
+	(0x2B740, 0x2B81F, CjkBlock::ExtensionD),
+	(0x2B820, 0x2CEAF, CjkBlock::ExtensionE),
+	(0x2CEB0, 0x2EBEF, CjkBlock::ExtensionF),
+	(0x2EBF0, 0x2EE5F, CjkBlock::ExtensionI),
+	(
+		0x2F800,
+		0x2FA1F,
+		CjkBlock::CompatibilityIdeographsSupplement,
+	),
+	(0x30000, 0x3134F, CjkBlock::ExtensionG),
+	(0x31350, 0x323AF, CjkBlock::ExtensionH),
+	(0x323B0, 0x3347F, CjkBlock::ExtensionJ),
+];
+
+impl CjkBlock {
+	/// The block `c` is in, if it's one of the CJK blocks.
+	#[must_use]
+	pub const fn of(c: char) -> Option<Self> {
+		#[allow(clippy::as_conversions, reason = "Every character is a valid u32")]
+		let c = c as u32;
+		let (mut low, mut high) = (0, BLOCKS.len());
+		while low < high {
+			let mid = low + (high - low) / 2;
+			let (start, end, block) = BLOCKS[mid];
+			if c < start {
+				high = mid;
+			} else if c > end {
+				low = mid + 1;
+			} else {
+				return Some(block);
+			}
+		}
+		None
+	}
+
+	#[must_use]
+	pub const fn script(&self) -> Script {
+		match self {
+			Self::UnifiedIdeographs
+			| Self::ExtensionA
+			| Self::ExtensionB
+			| Self::ExtensionC
+			| Self::ExtensionD
+			| Self::ExtensionE
+			| Self::ExtensionF
+			| Self::ExtensionG
+			| Self::ExtensionH
+			| Self::ExtensionI
+			| Self::ExtensionJ
+			| Self::CompatibilityIdeographs
+			| Self::CompatibilityIdeographsSupplement
+			| Self::KangxiRadicals
+			| Self::RadicalsSupplement => Script::Han,
+			Self::Hiragana
+			| Self::Katakana
+			| Self::KatakanaPhoneticExtensions
+			| Self::KanaSupplement
+			| Self::KanaExtendedA
+			| Self::KanaExtendedB
+			| Self::SmallKanaExtension => Script::Kana,
+			Self::HangulSyllables
+			| Self::HangulJamo
+			| Self::HangulJamoExtendedA
+			| Self::HangulJamoExtendedB
+			| Self::HangulCompatibilityJamo => Script::Hangul,
+			Self::Bopomofo | Self::BopomofoExtended => Script::Bopomofo,
+			Self::Strokes
+			| Self::IdeographicDescriptionCharacters
+			| Self::SymbolsAndPunctuation
+			| Self::IdeographicSymbolsAndPunctuation
+			| Self::EnclosedLettersAndMonths
+			| Self::EnclosedIdeographicSupplement
+			| Self::Compatibility
+			| Self::CompatibilityForms
+			| Self::Kanbun
+			| Self::HalfwidthAndFullwidthForms
+			| Self::VerticalForms
+			| Self::SmallFormVariants
+			| Self::YijingHexagramSymbols
+			| Self::TaiXuanJingSymbols
+			| Self::CountingRodNumerals => Script::Common,
+		}
+	}
+
+	/// Whether the block holds ideographs that can be learned and tested on, which includes the
+	/// compatibility ideographs but not the radicals.
+	#[must_use]
+	pub const fn is_ideograph(&self) -> bool {
+		matches!(self.script(), Script::Han) && !self.is_radical()
+	}
+
+	#[must_use]
+	pub const fn is_radical(&self) -> bool {
+		matches!(self, Self::KangxiRadicals | Self::RadicalsSupplement)
+	}
+}
+
+/// The ideograph for Kangxi radical `number`, from 1 to 214, like 人 for 9.
+#[must_use]
+pub fn kangxi_radical(number: u64) -> Option<char> {
+	if !(1..=214).contains(&number) {
+		return None;
+	}
+	let radical = char::from_u32(0x2F00 + u32::try_from(number - 1).ok()?)?;
+	let mut ideograph = None;
+	decompose_compatible(radical, |c| ideograph = Some(c));
+	ideograph
+}
+
+/// The script of `c` by its block, if it's in one of the CJK blocks. Halfwidth katakana and
+/// hangul are [`Script::Kana`] and [`Script::Hangul`], though their block is mostly
+/// [`Script::Common`].
+#[must_use]
+pub const fn script(c: char) -> Option<Script> {
+	match c {
+		'\u{FF66}'..='\u{FF9F}' => Some(Script::Kana),
+		'\u{FFA0}'..='\u{FFDC}' => Some(Script::Hangul),
+		_ => match CjkBlock::of(c) {
+			Some(block) => Some(block.script()),
+			None => None,
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+
+	use super::*;
+	use crate::is_chinese_character;
+
+	const BLOCKS_TXT: &str = include_str!("../data/Blocks.txt");
+
+	/// The blocks in `data/Blocks.txt`.
+	fn unicode_blocks() -> Vec<(u32, u32, String)> {
+		BLOCKS_TXT
+			.lines()
+			.filter(|line| !line.is_empty() && !line.starts_with('#'))
+			.map(|line| {
+				let (range, name) = line.split_once("; ").expect("range; name");
+				let (start, end) = range.split_once("..").expect("start..end");
+				let parse = |hex| u32::from_str_radix(hex, 16).expect("hex code point");
+				(parse(start), parse(end), name.to_owned())
+			})
+			.collect()
+	}
+
+	/// [`BLOCKS`] as generated from `data/Blocks.txt`: every block with a [`CjkBlock`] name.
+	fn generate_blocks() -> Vec<(u32, u32, CjkBlock)> {
+		unicode_blocks()
+			.into_iter()
+			.filter_map(|(start, end, name)| Some((start, end, name.parse().ok()?)))
+			.collect()
+	}
+
+	/// The Rust source of [`BLOCKS`] for `blocks`.
+	fn blocks_source(blocks: &[(u32, u32, CjkBlock)]) -> String {
+		let mut source = String::from("pub const BLOCKS: &[(u32, u32, CjkBlock)] = &[\n");
+		for (start, end, block) in blocks {
+			source += &format!("\t(0x{start:04X}, 0x{end:04X}, CjkBlock::{block:?}),\n");
+		}
+		source + "];\n"
+	}
+
+	#[test]
+	fn data_is_the_pinned_version() {
+		let (major, minor, update) = UNICODE_VERSION;
+		assert_eq!(
+			BLOCKS_TXT.lines().next(),
+			Some(format!("# Blocks-{major}.{minor}.{update}.txt").as_str()),
+			"data/Blocks.txt should be from Unicode {major}.{minor}.{update}"
+		);
+		assert!(
+			unicode_blocks().len() > 300,
+			"data/Blocks.txt should be the whole file"
+		);
+	}
+
+	#[test]
+	fn table_is_generated_from_the_unicode_data() {
+		let generated = generate_blocks();
+		assert!(
+			generated == BLOCKS,
+			"BLOCKS is out of date with data/Blocks.txt, replace it with:\n{}",
+			blocks_source(&generated)
+		);
+		let distinct: HashSet<_> = BLOCKS.iter().map(|(_, _, block)| block).collect();
+		assert_eq!(
+			distinct.len(),
+			CjkBlock::ALL.len(),
+			"every variant is in the data"
+		);
+		let east_asian = [
+			"CJK",
+			"Ideograph",
+			"Kangxi",
+			"Kanbun",
+			"Hiragana",
+			"Katakana",
+			"Kana",
+			"Hangul",
+			"Bopomofo",
+			"Halfwidth",
+			"Vertical Forms",
+			"Small Form",
+		];
+		for (_, _, name) in unicode_blocks() {
+			if east_asian.iter().any(|word| name.contains(word)) {
+				assert!(
+					name.parse::<CjkBlock>().is_ok(),
+					"{name} should be a CjkBlock"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn every_code_point_is_classified_by_its_block() {
+		let blocks = unicode_blocks();
+		for c in (0..=u32::from(char::MAX)).filter_map(char::from_u32) {
+			let expected = blocks
+				.iter()
+				.find(|(start, end, _)| (*start..=*end).contains(&u32::from(c)))
+				.and_then(|(_, _, name)| name.parse().ok());
+			let block = CjkBlock::of(c);
+			assert_eq!(block, expected, "block of U+{:04X}", u32::from(c));
+			assert_eq!(
+				is_chinese_character(&c),
+				block.is_some_and(|block| block.is_ideograph()),
+				"U+{:04X} is Chinese exactly when it's in an ideograph block",
+				u32::from(c)
+			);
+		}
+	}
+
+	#[test]
+	fn scripts() {
+		assert_eq!(script('漢'), Some(Script::Han), "ideograph");
+		assert_eq!(
+			script('\u{F91D}'),
+			Some(Script::Han),
+			"compatibility ideograph"
+		);
+		assert_eq!(script('⼈'), Some(Script::Han), "Kangxi radical");
+		assert_eq!(
+			(kangxi_radical(1), kangxi_radical(9), kangxi_radical(214)),
+			(Some('一'), Some('人'), Some('龠')),
+			"radical numbers to ideographs"
+		);
+		assert_eq!(kangxi_radical(215), None, "there are 214 radicals");
+		assert_eq!(script('か'), Some(Script::Kana), "hiragana");
+		assert_eq!(script('ㄅ'), Some(Script::Bopomofo), "bopomofo");
+		assert_eq!(script('한'), Some(Script::Hangul), "hangul");
+		assert_eq!(script('。'), Some(Script::Common), "punctuation");
+		assert_eq!(script('ｶ'), Some(Script::Kana), "halfwidth katakana");
+		assert_eq!(script('ﾡ'), Some(Script::Hangul), "halfwidth hangul");
+		assert_eq!(script('Ａ'), Some(Script::Common), "fullwidth latin");
+		assert_eq!(script('︐'), Some(Script::Common), "vertical forms");
+		assert_eq!(script('a'), None, "latin");
+		assert!(
+			!CjkBlock::KangxiRadicals.is_ideograph(),
+			"radicals aren't tested on"
+		);
+		assert_eq!(
+			CjkBlock::of('\u{323B0}'),
+			Some(CjkBlock::ExtensionJ),
+			"Extension J is new in Unicode 17"
+		);
+	}
+}
@@ -0,0 +1,429 @@
+//! Looking characters up in KANJIDIC2 by what someone knows about them: a reading, a meaning,
+//! the stroke count, the radical, or a SKIP or four-corner code.
+//!
+//! A query is split on whitespace and every term has to match:
+//!
+//! - `12` is a stroke count and `r61` a classical radical number.
+//! - `1-4-3` is a SKIP code, where any number can be `*`.
+//! - `4060` or `4060.0` is a four-corner code.
+//! - Anything else is a reading or a word of a meaning.
+//!
+//! Readings match whether they're typed in kana or romaji, Hepburn or Kunrei-shiki, so `gaku`,
+//! `がく` and `ガク` find the same characters. Tone numbers, tone marks and other diacritics are
+//! ignored, so `xue` finds `xue2` and `hoc` finds `Học`.
+
+use std::collections::{HashMap, HashSet};
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+	load_kanjidic::{Dict2, QueryCodeType, RadicalType},
+	query::SkipPattern,
+};
+
+/// What a term of a query looks for.
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+	Strokes(u64),
+	Radical(u64),
+	Skip(SkipPattern),
+	/// With or without the digit after the dot.
+	FourCorner(String),
+	Text(String),
+}
+
+impl Term {
+	fn parse(term: &str) -> Self {
+		let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+		if term.len() <= 3 && is_digits(term) {
+			if let Ok(strokes) = term.parse() {
+				return Self::Strokes(strokes);
+			}
+		}
+		if let Some(number) = term.strip_prefix(['r', 'R']).filter(|n| is_digits(n)) {
+			if let Ok(number) = number.parse() {
+				return Self::Radical(number);
+			}
+		}
+		if term.matches('-').count() == 2 {
+			if let Ok(pattern) = term.parse() {
+				return Self::Skip(pattern);
+			}
+		}
+		let (corners, extra) = term.split_once('.').unwrap_or((term, "0"));
+		if corners.len() == 4 && is_digits(corners) && extra.len() == 1 && is_digits(extra) {
+			return Self::FourCorner(term.to_owned());
+		}
+		Self::Text(term.to_owned())
+	}
+}
+
+/// What a search needs to know about a character.
+#[derive(Debug, Clone)]
+struct Entry {
+	c: char,
+	strokes: Option<u64>,
+	radical: Option<u64>,
+	freq: Option<u64>,
+	skip: Vec<String>,
+	four_corner: Vec<String>,
+}
+
+/// The characters of KANJIDIC2 by their readings and meanings.
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+	entries: Vec<Entry>,
+	/// Entries by every reading, normalized by [`normalize_reading`].
+	readings: HashMap<String, Vec<usize>>,
+	/// Entries by every word of their meanings in any language, lowercased.
+	meanings: HashMap<String, Vec<usize>>,
+}
+
+impl SearchIndex {
+	#[must_use]
+	pub fn new(dict: &Dict2) -> Self {
+		let mut index = Self::default();
+		for character in dict.characters() {
+			let Some(c) = character.literal().chars().next() else {
+				continue;
+			};
+			let i = index.entries.len();
+			let codes = |qc_type: QueryCodeType| {
+				character
+					.query_codes()
+					.filter(|code| code.qc_type() == &qc_type)
+					.map(|code| code.value().to_owned())
+					.collect()
+			};
+			index.entries.push(Entry {
+				c,
+				strokes: character.stroke_count(),
+				radical: character.radical(&RadicalType::Classical),
+				freq: character.freq(),
+				skip: codes(QueryCodeType::Skip),
+				four_corner: codes(QueryCodeType::FourCorner),
+			});
+			for reading in character.readings() {
+				let value = reading.value();
+				let stem = value.split('.').next().unwrap_or(value);
+				for key in [normalize_reading(value), normalize_reading(stem)] {
+					insert(&mut index.readings, key, i);
+				}
+			}
+			for meaning in character.meanings() {
+				for word in words(meaning.value()) {
+					insert(&mut index.meanings, word, i);
+				}
+			}
+		}
+		index
+	}
+
+	/// The characters matching every term of `query`, most frequent first, then by stroke
+	/// count. Nothing for an empty query.
+	#[must_use]
+	pub fn search(&self, query: &str) -> Vec<char> {
+		let terms: Vec<Term> = query.split_whitespace().map(Term::parse).collect();
+		if terms.is_empty() {
+			return Vec::new();
+		}
+		let mut matches: Option<HashSet<usize>> = None;
+		for term in &terms {
+			let Term::Text(text) = term else {
+				continue;
+			};
+			let found: HashSet<usize> = [
+				self.meanings.get(&text.to_lowercase()),
+				self.readings.get(&normalize_reading(text)),
+			]
+			.into_iter()
+			.flatten()
+			.flatten()
+			.copied()
+			.collect();
+			matches = Some(match matches {
+				Some(matches) => &matches & &found,
+				None => found,
+			});
+		}
+		let mut results: Vec<&Entry> = self
+			.entries
+			.iter()
+			.enumerate()
+			.filter(|(i, _)| matches.as_ref().is_none_or(|matches| matches.contains(i)))
+			.map(|(_, entry)| entry)
+			.filter(|entry| terms.iter().all(|term| entry.matches(term)))
+			.collect();
+		results.sort_by_key(|entry| {
+			(
+				entry.freq.unwrap_or(u64::MAX),
+				entry.strokes.unwrap_or(u64::MAX),
+			)
+		});
+		results.into_iter().map(|entry| entry.c).collect()
+	}
+}
+
+impl Entry {
+	/// Whether the entry matches the terms that aren't looked up in the index.
+	fn matches(&self, term: &Term) -> bool {
+		match term {
+			Term::Strokes(strokes) => self.strokes == Some(*strokes),
+			Term::Radical(radical) => self.radical == Some(*radical),
+			Term::Skip(pattern) => self.skip.iter().any(|code| pattern.matches(code)),
+			Term::FourCorner(code) => self.four_corner.iter().any(|own| {
+				own == code
+					|| own
+						.split_once('.')
+						.is_some_and(|(corners, _)| corners == code)
+			}),
+			Term::Text(_) => true,
+		}
+	}
+}
+
+fn insert(index: &mut HashMap<String, Vec<usize>>, key: String, i: usize) {
+	if key.is_empty() {
+		return;
+	}
+	let entries = index.entry(key).or_default();
+	if entries.last() != Some(&i) {
+		entries.push(i);
+	}
+}
+
+/// The lowercase words of a meaning, like `sign`, `of`, `the` and `ox`.
+fn words(meaning: &str) -> impl Iterator<Item = String> + '_ {
+	meaning
+		.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+		.map(str::to_lowercase)
+}
+
+/// One spelling for every way of writing a reading. Kana become romaji, and romaji lose their
+/// diacritics and tone numbers and are brought to Kunrei-shiki, so `shi`, `si` and `し` are all
+/// `si`. Hangul are left as they are.
+fn normalize_reading(reading: &str) -> String {
+	let mut plain = String::new();
+	for c in romanize(reading).chars() {
+		if is_hangul(c) {
+			plain.push(c);
+		} else if matches!(c, 'đ' | 'Đ') {
+			plain.push('d');
+		} else {
+			plain.extend(
+				c.nfd()
+					.filter(char::is_ascii_alphabetic)
+					.map(|c| c.to_ascii_lowercase()),
+			);
+		}
+	}
+	[
+		("tch", "tt"),
+		("shi", "si"),
+		("sh", "sy"),
+		("chi", "ti"),
+		("ch", "ty"),
+		("tsu", "tu"),
+		("fu", "hu"),
+		("ji", "zi"),
+		("j", "zy"),
+	]
+	.into_iter()
+	.fold(plain, |plain, (hepburn, kunrei)| {
+		plain.replace(hepburn, kunrei)
+	})
+}
+
+const fn is_hangul(c: char) -> bool {
+	matches!(c, '\u{AC00}'..='\u{D7A3}' | '\u{3130}'..='\u{318F}')
+}
+
+/// Kana in Hepburn romaji, like `gakkou` for がっこう. Anything else is kept.
+fn romanize(text: &str) -> String {
+	let mut romaji = String::new();
+	let mut double_next = false;
+	for c in text.chars() {
+		let c = to_hiragana(c);
+		match c {
+			'っ' => double_next = true,
+			'ゃ' | 'ゅ' | 'ょ' => {
+				let vowel = match c {
+					'ゃ' => 'a',
+					'ゅ' => 'u',
+					_ => 'o',
+				};
+				if romaji.ends_with('i') {
+					romaji.pop();
+				}
+				if !(romaji.ends_with("sh") || romaji.ends_with("ch") || romaji.ends_with('j')) {
+					romaji.push('y');
+				}
+				romaji.push(vowel);
+			}
+			'ー' => {
+				if let Some(vowel) = romaji.chars().last().filter(|c| "aiueo".contains(*c)) {
+					romaji.push(vowel);
+				}
+			}
+			_ => {
+				let syllable = hiragana_romaji(c);
+				if double_next {
+					double_next = false;
+					match syllable.and_then(|syllable| syllable.chars().next()) {
+						Some('c') => romaji.push('t'),
+						Some(consonant) if !"aiueon".contains(consonant) => romaji.push(consonant),
+						_ => {}
+					}
+				}
+				match syllable {
+					Some(syllable) => romaji.push_str(syllable),
+					None => romaji.push(c),
+				}
+			}
+		}
+	}
+	romaji
+}
+
+/// Katakana as the hiragana with the same sound. Anything else is kept.
+fn to_hiragana(c: char) -> char {
+	match c {
+		'\u{30A1}'..='\u{30F6}' => char::from_u32(u32::from(c) - 0x60).unwrap_or(c),
+		_ => c,
+	}
+}
+
+const fn hiragana_romaji(c: char) -> Option<&'static str> {
+	Some(match c {
+		'あ' | 'ぁ' => "a",
+		'い' | 'ぃ' | 'ゐ' => "i",
+		'う' | 'ぅ' => "u",
+		'え' | 'ぇ' | 'ゑ' => "e",
+		'お' | 'ぉ' | 'を' => "o",
+		'か' => "ka",
+		'き' => "ki",
+		'く' => "ku",
+		'け' => "ke",
+		'こ' => "ko",
+		'が' => "ga",
+		'ぎ' => "gi",
+		'ぐ' => "gu",
+		'げ' => "ge",
+		'ご' => "go",
+		'さ' => "sa",
+		'し' => "shi",
+		'す' => "su",
+		'せ' => "se",
+		'そ' => "so",
+		'ざ' => "za",
+		'じ' | 'ぢ' => "ji",
+		'ず' | 'づ' => "zu",
+		'ぜ' => "ze",
+		'ぞ' => "zo",
+		'た' => "ta",
+		'ち' => "chi",
+		'つ' => "tsu",
+		'て' => "te",
+		'と' => "to",
+		'だ' => "da",
+		'で' => "de",
+		'ど' => "do",
+		'な' => "na",
+		'に' => "ni",
+		'ぬ' => "nu",
+		'ね' => "ne",
+		'の' => "no",
+		'は' => "ha",
+		'ひ' => "hi",
+		'ふ' => "fu",
+		'へ' => "he",
+		'ほ' => "ho",
+		'ば' => "ba",
+		'び' => "bi",
+		'ぶ' => "bu",
+		'べ' => "be",
+		'ぼ' => "bo",
+		'ぱ' => "pa",
+		'ぴ' => "pi",
+		'ぷ' => "pu",
+		'ぺ' => "pe",
+		'ぽ' => "po",
+		'ま' => "ma",
+		'み' => "mi",
+		'む' => "mu",
+		'め' => "me",
+		'も' => "mo",
+		'や' => "ya",
+		'ゆ' => "yu",
+		'よ' => "yo",
+		'ら' => "ra",
+		'り' => "ri",
+		'る' => "ru",
+		'れ' => "re",
+		'ろ' => "ro",
+		'わ' => "wa",
+		'ん' => "n",
+		'ゔ' => "vu",
+		_ => return None,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::load_kanjidic::fixture;
+
+	fn search(query: &str) -> String {
+		SearchIndex::new(&fixture())
+			.search(query)
+			.into_iter()
+			.collect()
+	}
+
+	#[test]
+	fn romanization() {
+		assert_eq!(romanize("がっこう"), "gakkou", "small tsu");
+		assert_eq!(romanize("チュウ"), "chuu", "katakana and small ya, yu, yo");
+		assert_eq!(romanize("マッチ"), "matchi", "doubled ch");
+		assert_eq!(
+			normalize_reading("ひと.つ"),
+			normalize_reading("hitotu"),
+			"Kunrei-shiki and okurigana"
+		);
+		assert_eq!(normalize_reading("xué"), "xue", "tone marks");
+		assert_eq!(normalize_reading("yin3"), "yin", "tone numbers");
+		assert_eq!(normalize_reading("일"), "일", "hangul");
+	}
+
+	#[test]
+	fn by_reading_and_meaning() {
+		assert_eq!(search("ichi"), "一", "on reading in romaji");
+		assert_eq!(search("いち"), "一", "on reading in hiragana");
+		assert_eq!(search("hito"), "一", "kun reading without okurigana");
+		assert_eq!(search("tyuu"), "丑", "Kunrei-shiki");
+		assert_eq!(search("ミギ"), "右", "kun reading in katakana");
+		assert_eq!(search("Nhat"), "一", "Vietnamese without diacritics");
+		assert_eq!(search("yi"), "一異", "pinyin, most frequent first");
+		assert_eq!(search("Right"), "右", "meaning");
+		assert_eq!(search("ox"), "丑", "word of a meaning");
+		assert_eq!(search("uno"), "一", "meaning in another language");
+		assert_eq!(search("one pull"), "", "every term has to match");
+		assert_eq!(search("  "), "", "nothing for an empty query");
+	}
+
+	#[test]
+	fn by_codes() {
+		assert_eq!(search("5"), "右圧", "stroke count");
+		assert_eq!(search("5 r32"), "圧", "radical");
+		assert_eq!(search("3-2-3"), "右圧", "SKIP");
+		assert_eq!(search("2-*-4"), "悪", "SKIP with a wildcard");
+		assert_eq!(search("4060"), "右", "four-corner code");
+		assert_eq!(
+			search("1000.0"),
+			"一",
+			"four-corner code with the extra digit"
+		);
+		assert_eq!(search("ichi 1"), "一", "codes and readings together");
+	}
+}
@@ -0,0 +1,107 @@
+//! Combining character lists, like "Jōyō kanji minus the ones I know".
+
+use std::collections::HashSet;
+
+crate::derive_str! {
+	#[ignore_case]
+	/// Results keep the order of the left list. A union adds what's new in the right list
+	/// after it. Duplicates are dropped.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub enum SetOp {
+		Union = "union" | "∪" | "or",
+		Intersection = "intersection" | "∩" | "and",
+		Difference = "difference" | "−" | "-" | "minus",
+	}
+}
+
+impl SetOp {
+	#[must_use]
+	pub const fn symbol(&self) -> char {
+		match self {
+			Self::Union => '∪',
+			Self::Intersection => '∩',
+			Self::Difference => '−',
+		}
+	}
+
+	#[must_use]
+	pub fn apply(&self, left: &[char], right: &[char]) -> Vec<char> {
+		let right_set: HashSet<char> = right.iter().copied().collect();
+		let mut seen = HashSet::new();
+		let from_left = left.iter().copied().filter(|c| match self {
+			Self::Union => true,
+			Self::Intersection => right_set.contains(c),
+			Self::Difference => !right_set.contains(c),
+		});
+		let from_right = right.iter().copied().filter(|_| *self == Self::Union);
+		from_left
+			.chain(from_right)
+			.filter(|c| seen.insert(*c))
+			.collect()
+	}
+}
+
+/// Applies each operation in turn, left to right.
+///
+/// ```
+/// # use lib::set_op::{fold, SetOp};
+/// let joyo = ['一', '右', '引'];
+/// let known = ['一'];
+/// let extra = ['悪'];
+/// let to_learn = fold(&joyo, [(SetOp::Difference, &known[..]), (SetOp::Union, &extra[..])]);
+/// assert_eq!(to_learn, ['右', '引', '悪']);
+/// ```
+#[must_use]
+pub fn fold<'a>(first: &[char], ops: impl IntoIterator<Item = (SetOp, &'a [char])>) -> Vec<char> {
+	ops.into_iter()
+		.fold(SetOp::Union.apply(first, &[]), |acc, (op, list)| {
+			op.apply(&acc, list)
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn apply(op: SetOp, left: &str, right: &str) -> String {
+		let left: Vec<char> = left.chars().collect();
+		let right: Vec<char> = right.chars().collect();
+		op.apply(&left, &right).into_iter().collect()
+	}
+
+	#[test]
+	fn operations_keep_the_left_order() {
+		assert_eq!(
+			apply(SetOp::Union, "天地人", "人和天日"),
+			"天地人和日",
+			"union"
+		);
+		assert_eq!(
+			apply(SetOp::Intersection, "天地人", "人和天"),
+			"天人",
+			"intersection"
+		);
+		assert_eq!(
+			apply(SetOp::Difference, "天地人", "人和"),
+			"天地",
+			"difference"
+		);
+	}
+
+	#[test]
+	fn duplicates_are_dropped() {
+		assert_eq!(apply(SetOp::Union, "天天地", "地地"), "天地", "union");
+		assert_eq!(
+			apply(SetOp::Intersection, "天天地", "天"),
+			"天",
+			"intersection"
+		);
+	}
+
+	#[test]
+	fn parses_symbols() {
+		assert_eq!("∩".parse(), Ok(SetOp::Intersection), "symbol");
+		assert_eq!("minus".parse(), Ok(SetOp::Difference), "word");
+		assert_eq!(SetOp::Union.symbol(), '∪', "symbol");
+	}
+}
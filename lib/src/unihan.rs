@@ -0,0 +1,490 @@
+//! Reading the tab-separated text files of the Unihan database, like `Unihan_Variants.txt`.
+//!
+//! Every line is `U+XXXX<TAB>kField<TAB>value`. Lines starting with `#` are comments.
+
+use std::{
+	collections::HashMap,
+	fs::{self, File},
+	io::{self, BufRead, BufReader},
+	path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::variant::VariantKind;
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("Failed to open {}", path.display())]
+	Io { source: io::Error, path: PathBuf },
+	#[error("Failed to read Unihan data")]
+	Read(#[from] io::Error),
+	#[error("Line {line} isn't a Unihan entry: {text}")]
+	Syntax { line: usize, text: String },
+}
+
+/// Calls `entry` with the character, field name and value of every line in `reader`.
+///
+/// # Errors
+///
+/// Returns an error if `reader` fails or a line isn't a Unihan entry.
+pub fn read_entries(
+	reader: impl BufRead,
+	mut entry: impl FnMut(char, &str, &str),
+) -> Result<(), Error> {
+	for (index, line) in reader.lines().enumerate() {
+		let line = line?;
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let mut fields = line.splitn(3, '\t');
+		match (
+			fields.next().and_then(parse_code_point),
+			fields.next(),
+			fields.next(),
+		) {
+			(Some(c), Some(field), Some(value)) => entry(c, field, value),
+			_ => {
+				return Err(Error::Syntax {
+					line: index + 1,
+					text: line,
+				})
+			}
+		}
+	}
+	Ok(())
+}
+
+/// [`read_entries`] on the file at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read or a line isn't a Unihan entry.
+pub fn read_file(path: &Path, entry: impl FnMut(char, &str, &str)) -> Result<(), Error> {
+	let file = File::open(path).map_err(|source| Error::Io {
+		source,
+		path: path.to_owned(),
+	})?;
+	read_entries(BufReader::new(file), entry)
+}
+
+/// Parses a `U+XXXX` code point, ignoring anything after it that starts with `<`, like the
+/// sources in `U+5B66<kMatthews`.
+#[must_use]
+pub fn parse_code_point(s: &str) -> Option<char> {
+	let hex = s.strip_prefix("U+")?;
+	let hex = hex.split_once('<').map_or(hex, |(hex, _)| hex);
+	char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+}
+
+/// The fields [`Unihan`] keeps.
+const FIELDS: [&str; 17] = [
+	"kMandarin",
+	"kCantonese",
+	"kHangul",
+	"kKorean",
+	"kVietnamese",
+	"kDefinition",
+	"kTotalStrokes",
+	"kRSUnicode",
+	"kFrequency",
+	"kGradeLevel",
+	"kPhonetic",
+	"kTGH",
+	"kKoreanName",
+	"kSimplifiedVariant",
+	"kTraditionalVariant",
+	"kZVariant",
+	"kSemanticVariant",
+];
+
+/// The readings, definitions, indexing data, variants and list memberships Unihan has for
+/// characters, which covers the Chinese and Korean side KANJIDIC2 is thin on.
+#[derive(Debug, Clone, Default)]
+pub struct Unihan {
+	entries: HashMap<char, Entry>,
+}
+
+impl Unihan {
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Reads every `Unihan_*.txt` file in `dir`, as extracted from `Unihan.zip`. Which file a
+	/// field is in has changed between Unicode versions, so all of them are read.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `dir` or one of the files can't be read, or a file isn't Unihan data.
+	pub fn load_dir(dir: &Path) -> Result<Self, Error> {
+		let io_error = |source| Error::Io {
+			source,
+			path: dir.to_owned(),
+		};
+		let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+			.map_err(io_error)?
+			.map(|entry| entry.map(|entry| entry.path()))
+			.collect::<Result<_, _>>()
+			.map_err(io_error)?;
+		paths.retain(|path| {
+			let is_text = path
+				.extension()
+				.is_some_and(|ext| ext.eq_ignore_ascii_case("txt"));
+			let is_unihan = path
+				.file_name()
+				.and_then(|name| name.to_str())
+				.is_some_and(|name| name.starts_with("Unihan_"));
+			is_text && is_unihan
+		});
+		paths.sort();
+		let mut unihan = Self::new();
+		for path in paths {
+			read_file(&path, |c, field, value| unihan.insert(c, field, value))?;
+		}
+		Ok(unihan)
+	}
+
+	/// Adds the entries in `reader`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `reader` fails or isn't Unihan data.
+	pub fn read(&mut self, reader: impl BufRead) -> Result<(), Error> {
+		read_entries(reader, |c, field, value| self.insert(c, field, value))
+	}
+
+	fn insert(&mut self, c: char, field: &str, value: &str) {
+		let words = || value.split(' ').map(str::to_owned).collect();
+		let numbers = || value.split(' ').filter_map(|n| n.parse().ok()).collect();
+		if !FIELDS.contains(&field) {
+			return;
+		}
+		let entry = self.entries.entry(c).or_default();
+		match field {
+			"kMandarin" => entry.mandarin = words(),
+			"kCantonese" => entry.cantonese = words(),
+			"kHangul" => {
+				entry.hangul = value
+					.split(' ')
+					.map(|reading| reading.split_once(':').map_or(reading, |(r, _)| r))
+					.map(str::to_owned)
+					.collect();
+			}
+			"kKorean" => entry.korean = words(),
+			"kVietnamese" => entry.vietnamese = words(),
+			"kDefinition" => entry.definition = Some(value.to_owned()),
+			"kTotalStrokes" => entry.total_strokes = numbers(),
+			"kRSUnicode" => {
+				entry.radical_strokes = value.split(' ').filter_map(RadicalStroke::parse).collect();
+			}
+			"kFrequency" => entry.frequency = value.parse().ok(),
+			"kGradeLevel" => entry.grade_level = value.parse().ok(),
+			"kPhonetic" => {
+				entry.phonetic_series = value
+					.split(' ')
+					.filter_map(|series| {
+						let digits = series.find(|c: char| !c.is_ascii_digit());
+						series[..digits.unwrap_or(series.len())].parse().ok()
+					})
+					.collect();
+			}
+			"kTGH" => {
+				entry.general_standard = value
+					.split(' ')
+					.find_map(|position| position.split_once(':')?.1.parse().ok());
+			}
+			"kKoreanName" => entry.korean_name = true,
+			field => {
+				if let Ok(kind) = field.parse() {
+					let variants = value.split(' ').filter_map(parse_code_point);
+					entry
+						.variants
+						.extend(variants.map(|variant| (kind, variant)));
+				}
+			}
+		}
+	}
+
+	#[must_use]
+	pub fn get(&self, c: char) -> Option<&Entry> {
+		self.entries.get(&c)
+	}
+
+	/// Every character with an entry, in no particular order.
+	pub fn iter(&self) -> impl Iterator<Item = (char, &Entry)> {
+		self.entries.iter().map(|(&c, entry)| (c, entry))
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+}
+
+/// The fields of one character that [`Unihan`] keeps. Fields Unihan doesn't have for the
+/// character are empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Entry {
+	mandarin: Vec<String>,
+	cantonese: Vec<String>,
+	hangul: Vec<String>,
+	korean: Vec<String>,
+	vietnamese: Vec<String>,
+	definition: Option<String>,
+	total_strokes: Vec<u64>,
+	radical_strokes: Vec<RadicalStroke>,
+	frequency: Option<u64>,
+	grade_level: Option<u64>,
+	phonetic_series: Vec<u32>,
+	general_standard: Option<u32>,
+	korean_name: bool,
+	variants: Vec<(VariantKind, char)>,
+}
+
+impl Entry {
+	/// Pinyin with tone marks, the most common reading first.
+	#[must_use]
+	pub fn mandarin(&self) -> &[String] {
+		&self.mandarin
+	}
+
+	/// Jyutping, like `hok6`.
+	#[must_use]
+	pub fn cantonese(&self) -> &[String] {
+		&self.cantonese
+	}
+
+	#[must_use]
+	pub fn hangul(&self) -> &[String] {
+		&self.hangul
+	}
+
+	/// Korean readings in Yale romanization, in upper case.
+	#[must_use]
+	pub fn korean(&self) -> &[String] {
+		&self.korean
+	}
+
+	#[must_use]
+	pub fn vietnamese(&self) -> &[String] {
+		&self.vietnamese
+	}
+
+	/// An English definition, with senses separated by semicolons.
+	#[must_use]
+	pub fn definition(&self) -> Option<&str> {
+		self.definition.as_deref()
+	}
+
+	/// The stroke count for China first, then the one for other regions if it differs.
+	#[must_use]
+	pub fn total_strokes(&self) -> &[u64] {
+		&self.total_strokes
+	}
+
+	#[must_use]
+	pub fn radical_strokes(&self) -> &[RadicalStroke] {
+		&self.radical_strokes
+	}
+
+	/// How common the character is in traditional Chinese text, from 1 (most common) to 5.
+	#[must_use]
+	pub const fn frequency(&self) -> Option<u64> {
+		self.frequency
+	}
+
+	/// The grade of primary school in Hong Kong the character is taught in, from 1 to 6.
+	#[must_use]
+	pub const fn grade_level(&self) -> Option<u64> {
+		self.grade_level
+	}
+
+	/// The numbers of the phonetic series in Casey's "Chinese Characters" the character belongs
+	/// to. Characters in the same series share a sound component.
+	#[must_use]
+	pub fn phonetic_series(&self) -> &[u32] {
+		&self.phonetic_series
+	}
+
+	/// The position in the 通用规范汉字表, from 1 to 8105. Tier 1 is 1 to 3500, tier 2 up to
+	/// 6500 and tier 3 the rest.
+	#[must_use]
+	pub const fn general_standard(&self) -> Option<u32> {
+		self.general_standard
+	}
+
+	/// Whether the character is on the list of hanja for personal names (인명용 한자).
+	#[must_use]
+	pub const fn korean_name(&self) -> bool {
+		self.korean_name
+	}
+
+	/// The simplified, traditional, Z and semantic variants, in the order Unihan lists them.
+	#[must_use]
+	pub fn variants(&self) -> &[(VariantKind, char)] {
+		&self.variants
+	}
+}
+
+/// A `kRSUnicode` value: the Kangxi radical a character is filed under, and its number of
+/// strokes besides the radical.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RadicalStroke {
+	radical: u64,
+	simplified: bool,
+	residual_strokes: i64,
+}
+
+impl RadicalStroke {
+	/// Parses `39.5`. `120'.3` uses the simplified form of radical 120.
+	#[must_use]
+	pub fn parse(s: &str) -> Option<Self> {
+		let (radical, residual) = s.split_once('.')?;
+		let simplified = radical.ends_with('\'');
+		Some(Self {
+			radical: radical.trim_end_matches('\'').parse().ok()?,
+			simplified,
+			residual_strokes: residual.parse().ok()?,
+		})
+	}
+
+	#[must_use]
+	pub const fn radical(&self) -> u64 {
+		self.radical
+	}
+
+	/// Whether the character uses the simplified form of the radical, like 讠 for 言.
+	#[must_use]
+	pub const fn simplified(&self) -> bool {
+		self.simplified
+	}
+
+	#[must_use]
+	pub const fn residual_strokes(&self) -> i64 {
+		self.residual_strokes
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const READINGS: &str = "\
+U+5B66\tkCantonese\thok6
+U+5B66\tkDefinition\tlearning, knowledge; school
+U+5B66\tkHangul\t학:0N
+U+5B66\tkMandarin\txué
+U+5B66\tkTotalStrokes\t8
+U+5B66\tkRSUnicode\t39.5
+U+5B66\tkTGH\t2013:548
+U+5B78\tkKorean\tHAK
+U+5B78\tkVietnamese\thọc
+U+5B78\tkFrequency\t1
+U+5B78\tkGradeLevel\t1
+U+5B78\tkPhonetic\t1437 1509x*
+U+5B78\tkKoreanName\t2007
+U+5B78\tkSimplifiedVariant\tU+5B66
+U+5B78\tkSpoofingVariant\tU+6588
+U+8BA1\tkRSUnicode\t149'.2
+U+8BA1\tkIRG_GSource\tG0-3C46
+";
+
+	#[test]
+	fn keeps_the_fields_it_knows() {
+		let mut unihan = Unihan::new();
+		unihan.read(READINGS.as_bytes()).expect("valid data");
+		assert_eq!(unihan.len(), 3, "one entry per character");
+		let xue = unihan.get('学').expect("学 has entries");
+		assert_eq!(xue.mandarin(), ["xué"], "kMandarin");
+		assert_eq!(xue.cantonese(), ["hok6"], "kCantonese");
+		assert_eq!(xue.hangul(), ["학"], "kHangul without its source");
+		assert_eq!(
+			xue.definition(),
+			Some("learning, knowledge; school"),
+			"kDefinition"
+		);
+		assert_eq!(xue.total_strokes(), [8], "kTotalStrokes");
+		assert_eq!(
+			xue.radical_strokes().first().map(RadicalStroke::radical),
+			Some(39),
+			"kRSUnicode"
+		);
+		let hak = unihan.get('學').expect("學 has entries");
+		assert_eq!(
+			(hak.korean(), hak.vietnamese()),
+			(&["HAK".to_owned()][..], &["học".to_owned()][..]),
+			"kKorean and kVietnamese"
+		);
+		assert_eq!(
+			(hak.frequency(), hak.grade_level()),
+			(Some(1), Some(1)),
+			"kFrequency and kGradeLevel"
+		);
+		assert_eq!(
+			hak.phonetic_series(),
+			[1437, 1509],
+			"kPhonetic without its markers"
+		);
+		assert_eq!(
+			(xue.general_standard(), hak.general_standard()),
+			(Some(548), None),
+			"kTGH without its year"
+		);
+		assert!(hak.korean_name(), "kKoreanName");
+		assert_eq!(
+			hak.variants(),
+			[(VariantKind::Simplified, '学')],
+			"variant fields, without kSpoofingVariant"
+		);
+		let ji = unihan.get('计').expect("计 has entries");
+		assert!(ji.radical_strokes()[0].simplified(), "simplified radical");
+	}
+
+	#[test]
+	fn reads_entries_and_skips_comments() {
+		let data = "# Unihan_Variants.txt\n\nU+5B66\tkTraditionalVariant\tU+5B78\nU+5B78\tkSimplifiedVariant\tU+5B66\n";
+		let mut entries = Vec::new();
+		read_entries(data.as_bytes(), |c, field, value| {
+			entries.push((c, field.to_owned(), value.to_owned()));
+		})
+		.expect("valid data");
+		assert_eq!(
+			entries,
+			[
+				('学', "kTraditionalVariant".to_owned(), "U+5B78".to_owned()),
+				('學', "kSimplifiedVariant".to_owned(), "U+5B66".to_owned()),
+			],
+			"one entry per line"
+		);
+	}
+
+	#[test]
+	fn bad_lines_are_errors() {
+		let err = read_entries(
+			"U+5B66\tkTraditionalVariant\tU+5B78\nnonsense\n".as_bytes(),
+			|_, _, _| {},
+		)
+		.expect_err("not an entry");
+		assert!(
+			matches!(err, Error::Syntax { line: 2, .. }),
+			"unexpected error: {err:?}"
+		);
+	}
+
+	#[test]
+	fn code_points() {
+		assert_eq!(parse_code_point("U+4E00"), Some('一'), "plain");
+		assert_eq!(
+			parse_code_point("U+5B66<kMatthews,kMeyerWempe"),
+			Some('学'),
+			"with sources"
+		);
+		assert_eq!(parse_code_point("4E00"), None, "no prefix");
+	}
+}
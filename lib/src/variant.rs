@@ -0,0 +1,237 @@
+//! Characters that are written differently but are the same character, like 学 and 學.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+	load_kanjidic::{Dict2, MiscVariant, VariantType},
+	unihan::Unihan,
+};
+
+crate::derive_str! {
+	/// Where a variant comes from. The Unihan ones parse from their field names.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum VariantKind {
+		Simplified = "simplified" | "kSimplifiedVariant",
+		Traditional = "traditional" | "kTraditionalVariant",
+		/// The same character with a slightly different glyph.
+		Z = "z" | "kZVariant",
+		/// Interchangeable in meaning, like 峰 and 峯.
+		Semantic = "semantic" | "kSemanticVariant",
+		/// A `<variant>` in KANJIDIC2, usually an old or alternative form of a kanji.
+		Kanjidic = "kanjidic",
+	}
+}
+
+impl VariantKind {
+	#[must_use]
+	pub const fn label(&self) -> &'static str {
+		match self {
+			Self::Simplified => "Simplified",
+			Self::Traditional => "Traditional",
+			Self::Z => "Same character",
+			Self::Semantic => "Same meaning",
+			Self::Kanjidic => "Variant",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Variants {
+	variants: HashMap<char, Vec<(VariantKind, char)>>,
+}
+
+impl Variants {
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The variants KANJIDIC2 gives as JIS or Unicode code points. The ones given as dictionary
+	/// index numbers are skipped. KANJIDIC2 only lists one direction, so both are added.
+	#[must_use]
+	pub fn from_dict(dict: &Dict2) -> Self {
+		let mut variants = Self::new();
+		for character in dict.characters() {
+			let Some(c) = character.literal().chars().next() else {
+				continue;
+			};
+			for variant in character.variants().filter_map(resolve) {
+				variants.insert(c, VariantKind::Kanjidic, variant);
+				variants.insert(variant, VariantKind::Kanjidic, c);
+			}
+		}
+		variants
+	}
+
+	/// Adds the simplified, traditional, Z and semantic variants in `unihan`.
+	pub fn add_unihan(&mut self, unihan: &Unihan) {
+		for (c, entry) in unihan.iter() {
+			for &(kind, variant) in entry.variants() {
+				self.insert(c, kind, variant);
+			}
+		}
+	}
+
+	pub fn insert(&mut self, c: char, kind: VariantKind, variant: char) {
+		if c == variant {
+			return;
+		}
+		let variants = self.variants.entry(c).or_default();
+		if !variants.contains(&(kind, variant)) {
+			variants.push((kind, variant));
+		}
+	}
+
+	/// The variants of `c`, in the order they were added.
+	#[must_use]
+	pub fn of(&self, c: char) -> &[(VariantKind, char)] {
+		self.variants.get(&c).map_or(&[], Vec::as_slice)
+	}
+
+	/// `c` followed by every character that can be reached from it through variants, nearest
+	/// first.
+	#[must_use]
+	pub fn group(&self, c: char) -> Vec<char> {
+		let mut group = vec![c];
+		let mut queue = VecDeque::from([c]);
+		while let Some(c) = queue.pop_front() {
+			for (_, variant) in self.of(c) {
+				if !group.contains(variant) {
+					group.push(*variant);
+					queue.push_back(*variant);
+				}
+			}
+		}
+		group
+	}
+
+	/// Keeps only the first character of every variant group, so a test doesn't ask about 学
+	/// and 學 separately.
+	#[must_use]
+	pub fn collapse(&self, chars: &[char]) -> Vec<char> {
+		let mut seen = HashSet::new();
+		chars
+			.iter()
+			.copied()
+			.filter(|c| {
+				if seen.contains(c) {
+					return false;
+				}
+				seen.extend(self.group(*c));
+				true
+			})
+			.collect()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.variants.is_empty()
+	}
+}
+
+/// The character a KANJIDIC2 `<variant>` points to, if it's given as a JIS X 0208, JIS X 0212
+/// or Unicode code point.
+#[must_use]
+pub fn resolve(variant: &MiscVariant) -> Option<char> {
+	let value = variant.value();
+	match variant.var_type() {
+		VariantType::Ucs => char::from_u32(u32::from_str_radix(value, 16).ok()?),
+		VariantType::Jis208 => decode_euc_jp(&euc_jp_bytes(value)?),
+		VariantType::Jis212 => {
+			let [row, cell] = euc_jp_bytes(value)?;
+			decode_euc_jp(&[0x8F, row, cell])
+		}
+		_ => None,
+	}
+}
+
+/// The two EUC-JP bytes of a `plane-row-cell` JIS code, like `1-48-19`.
+fn euc_jp_bytes(kuten: &str) -> Option<[u8; 2]> {
+	let mut parts = kuten.split('-').skip(1).map(str::parse::<u8>);
+	match (parts.next(), parts.next(), parts.next()) {
+		(Some(Ok(row @ 1..=94)), Some(Ok(cell @ 1..=94)), None) => Some([row + 0xA0, cell + 0xA0]),
+		_ => None,
+	}
+}
+
+fn decode_euc_jp(bytes: &[u8]) -> Option<char> {
+	let decoded = encoding_rs::EUC_JP.decode_without_bom_handling_and_without_replacement(bytes)?;
+	let mut chars = decoded.chars();
+	match (chars.next(), chars.next()) {
+		(Some(c), None) => Some(c),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::load_kanjidic::fixture;
+
+	const UNIHAN: &str = "\
+U+5B66\tkTraditionalVariant\tU+5B78
+U+5B78\tkSimplifiedVariant\tU+5B66
+U+5CF0\tkSemanticVariant\tU+5CEF<kMatthews
+U+5CEF\tkSemanticVariant\tU+5CF0<kMatthews
+U+5B78\tkSpoofingVariant\tU+6588
+";
+
+	fn unihan() -> Variants {
+		let mut unihan = Unihan::new();
+		unihan.read(UNIHAN.as_bytes()).expect("valid data");
+		let mut variants = Variants::new();
+		variants.add_unihan(&unihan);
+		variants
+	}
+
+	#[test]
+	fn reads_unihan_variants() {
+		let variants = unihan();
+		assert_eq!(
+			variants.of('学'),
+			[(VariantKind::Traditional, '學')],
+			"traditional"
+		);
+		assert_eq!(
+			variants.of('峰'),
+			[(VariantKind::Semantic, '峯')],
+			"sources are ignored"
+		);
+		assert_eq!(
+			variants.of('學'),
+			[(VariantKind::Simplified, '学')],
+			"other fields are ignored"
+		);
+	}
+
+	#[test]
+	fn resolves_kanjidic_variants() {
+		let variants = Variants::from_dict(&fixture());
+		assert_eq!(
+			variants.of('亜'),
+			[(VariantKind::Kanjidic, '亞')],
+			"JIS X 0208"
+		);
+		assert_eq!(
+			variants.of('亞'),
+			[(VariantKind::Kanjidic, '亜')],
+			"both ways"
+		);
+		assert_eq!(
+			variants.of('亙'),
+			[(VariantKind::Kanjidic, '亘')],
+			"JIS X 0208"
+		);
+	}
+
+	#[test]
+	fn collapses_groups_to_the_first_member() {
+		let variants = unihan();
+		assert_eq!(
+			variants.collapse(&['學', '峯', '学', '山', '峰']),
+			['學', '峯', '山'],
+			"first seen wins"
+		);
+		assert_eq!(variants.group('学'), ['学', '學'], "nearest first");
+	}
+}
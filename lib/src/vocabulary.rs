@@ -0,0 +1,227 @@
+//! How many words of a dictionary can be read with the characters someone knows, and which
+//! words are only one character away.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{coverage::ratio, is_chinese_character};
+
+/// A dictionary word with its written forms, like the simplified and traditional ones, and how
+/// common it is. Weights only need to be comparable within one dictionary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedWord<'a> {
+	forms: Vec<&'a str>,
+	weight: f64,
+}
+
+impl<'a> WeightedWord<'a> {
+	#[must_use]
+	pub const fn new(forms: Vec<&'a str>, weight: f64) -> Self {
+		Self { forms, weight }
+	}
+}
+
+/// A word that can be read once one more character is known.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NearWord<'a> {
+	form: &'a str,
+	missing: char,
+	weight: f64,
+}
+
+impl<'a> NearWord<'a> {
+	#[must_use]
+	pub const fn form(&self) -> &'a str {
+		self.form
+	}
+
+	#[must_use]
+	pub const fn missing(&self) -> char {
+		self.missing
+	}
+
+	#[must_use]
+	pub const fn weight(&self) -> f64 {
+		self.weight
+	}
+}
+
+/// Words count as readable when every Chinese character in one of their forms is known; kana
+/// and other scripts are ignored. Words without any Chinese characters are left out entirely.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vocabulary<'a> {
+	words: usize,
+	weight: f64,
+	readable: Vec<(&'a str, f64)>,
+	readable_weight: f64,
+	one_away: Vec<NearWord<'a>>,
+}
+
+impl<'a> Vocabulary<'a> {
+	#[must_use]
+	pub fn analyze(
+		words: impl IntoIterator<Item = WeightedWord<'a>>,
+		known: &HashSet<char>,
+	) -> Self {
+		let mut vocabulary = Self {
+			words: 0,
+			weight: 0.0,
+			readable: Vec::new(),
+			readable_weight: 0.0,
+			one_away: Vec::new(),
+		};
+		for word in words {
+			let best = word
+				.forms
+				.iter()
+				.filter_map(|form| Some((*form, unknown_characters(form, known)?)))
+				.min_by_key(|(_, unknown)| unknown.len());
+			let Some((form, unknown)) = best else {
+				continue;
+			};
+			vocabulary.words += 1;
+			vocabulary.weight += word.weight;
+			match unknown[..] {
+				[] => {
+					vocabulary.readable.push((form, word.weight));
+					vocabulary.readable_weight += word.weight;
+				}
+				[missing] => vocabulary.one_away.push(NearWord {
+					form,
+					missing,
+					weight: word.weight,
+				}),
+				_ => {}
+			}
+		}
+		vocabulary.readable.sort_by(|a, b| b.1.total_cmp(&a.1));
+		vocabulary
+			.one_away
+			.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+		vocabulary
+	}
+
+	/// The number of words with Chinese characters in them.
+	#[must_use]
+	pub const fn words(&self) -> usize {
+		self.words
+	}
+
+	#[must_use]
+	pub const fn readable_words(&self) -> usize {
+		self.readable.len()
+	}
+
+	/// The readable forms with their weights, most common first.
+	#[must_use]
+	pub fn readable(&self) -> &[(&'a str, f64)] {
+		&self.readable
+	}
+
+	/// The share of words that are readable, from 0 to 1, counting every word the same.
+	#[must_use]
+	pub fn word_coverage(&self) -> f64 {
+		ratio(self.readable.len(), self.words)
+	}
+
+	/// The share of words that are readable, from 0 to 1, with common words counting for more.
+	#[must_use]
+	pub fn weighted_coverage(&self) -> f64 {
+		if self.weight == 0.0 {
+			1.0
+		} else {
+			self.readable_weight / self.weight
+		}
+	}
+
+	/// Words with exactly one unknown character, most common first.
+	#[must_use]
+	pub fn one_away(&self) -> &[NearWord<'a>] {
+		&self.one_away
+	}
+
+	/// The unknown characters that would make the most words readable, with the total weight
+	/// of those words, best first.
+	#[must_use]
+	pub fn unlocks(&self) -> Vec<(char, f64)> {
+		let mut unlocks: HashMap<char, f64> = HashMap::new();
+		for word in &self.one_away {
+			*unlocks.entry(word.missing).or_default() += word.weight;
+		}
+		let mut unlocks: Vec<(char, f64)> = unlocks.into_iter().collect();
+		unlocks.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+		unlocks
+	}
+}
+
+/// The distinct unknown Chinese characters in `form`, or `None` if it has no Chinese characters.
+fn unknown_characters(form: &str, known: &HashSet<char>) -> Option<Vec<char>> {
+	let mut chars: Vec<char> = form.chars().filter(is_chinese_character).collect();
+	if chars.is_empty() {
+		return None;
+	}
+	chars.retain(|c| !known.contains(c));
+	chars.sort_unstable();
+	chars.dedup();
+	Some(chars)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn words() -> Vec<WeightedWord<'static>> {
+		vec![
+			WeightedWord::new(vec!["学生", "學生"], 4.0),
+			WeightedWord::new(vec!["大学", "大學"], 2.0),
+			WeightedWord::new(vec!["中国", "中國"], 1.0),
+			WeightedWord::new(vec!["愛する"], 1.0),
+			WeightedWord::new(vec!["アイス"], 8.0),
+		]
+	}
+
+	#[test]
+	fn readable_words() {
+		let known = HashSet::from(['学', '生', '大', '愛']);
+		let vocabulary = Vocabulary::analyze(words(), &known);
+		assert_eq!(vocabulary.words(), 4, "words without kanji are left out");
+		assert_eq!(
+			vocabulary.readable(),
+			[("学生", 4.0), ("大学", 2.0), ("愛する", 1.0)],
+			"the known form of each word, kana ignored"
+		);
+		assert!(
+			(vocabulary.word_coverage() - 0.75).abs() < f64::EPSILON,
+			"three of four words"
+		);
+		assert!(
+			(vocabulary.weighted_coverage() - 7.0 / 8.0).abs() < f64::EPSILON,
+			"weighted by frequency"
+		);
+	}
+
+	#[test]
+	fn one_character_away() {
+		let known = HashSet::from(['学', '中']);
+		let vocabulary = Vocabulary::analyze(words(), &known);
+		let one_away: Vec<(&str, char)> = vocabulary
+			.one_away()
+			.iter()
+			.map(|word| (word.form(), word.missing()))
+			.collect();
+		assert_eq!(
+			one_away,
+			[
+				("学生", '生'),
+				("大学", '大'),
+				("中国", '国'),
+				("愛する", '愛')
+			],
+			"most common first"
+		);
+		assert_eq!(
+			vocabulary.unlocks()[0],
+			('生', 4.0),
+			"the character that unlocks the most weight first"
+		);
+	}
+}
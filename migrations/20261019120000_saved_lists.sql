@@ -0,0 +1,17 @@
+CREATE TABLE saved_list (
+    id INTEGER NOT NULL PRIMARY KEY,
+    profile INTEGER NOT NULL REFERENCES user_profile(id)
+        ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    text TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- The saved list being edited. When it's NULL, the text in user_profile_raw_test_text is.
+ALTER TABLE user_profile ADD COLUMN current_list INTEGER
+    REFERENCES saved_list(id) ON DELETE SET NULL;
+
+-- The saved list the current test was created from.
+ALTER TABLE user_profile ADD COLUMN test_list INTEGER
+    REFERENCES saved_list(id) ON DELETE SET NULL;
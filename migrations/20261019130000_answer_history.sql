@@ -0,0 +1,11 @@
+-- Every answer given in a test, so each character's history survives new tests.
+CREATE TABLE answer (
+    id INTEGER NOT NULL PRIMARY KEY,
+    profile INTEGER NOT NULL REFERENCES user_profile(id)
+        ON DELETE CASCADE,
+    char INTEGER NOT NULL,
+    known BOOLEAN NOT NULL,
+    answered_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX answer_profile_char ON answer (profile, char);
@@ -0,0 +1,5 @@
+-- Whether a character of the current test has been answered, so the ones a test didn't reach
+-- yet aren't counted as unknown.
+ALTER TABLE user_profile_characters ADD COLUMN answered BOOLEAN NOT NULL DEFAULT false;
+
+UPDATE user_profile_characters SET answered = known;
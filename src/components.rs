@@ -0,0 +1,293 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use lib::ids::Ids;
+use relm4::adw::prelude::*;
+use relm4::component;
+use relm4::gtk;
+use relm4::prelude::*;
+
+#[derive(Debug)]
+pub struct ComponentsScreen {
+	ids: Arc<Ids>,
+	/// The characters of the list being browsed.
+	set: Vec<char>,
+	known: HashSet<char>,
+	/// The components of the selected character.
+	components: Vec<char>,
+	/// The characters of the set sharing the selected component.
+	sharing: Vec<char>,
+	selected: Option<char>,
+	component: Option<char>,
+	character_list: gtk::ListBox,
+	component_list: gtk::ListBox,
+	sharing_list: gtk::ListBox,
+}
+
+#[component(pub, async)]
+impl SimpleAsyncComponent for ComponentsScreen {
+	type Init = Arc<Ids>;
+	type Input = Message;
+	type Output = OutputMessage;
+
+	view! {
+		#[root]
+		gtk::Box {
+			set_orientation: gtk::Orientation::Vertical,
+			set_hexpand: true,
+			gtk::Label {
+				set_css_classes: &["heading"],
+				set_label: "Components",
+			},
+			gtk::Label {
+				set_css_classes: &["mb-2"],
+				#[watch]
+				set_label: intro(&model.ids),
+			},
+			gtk::Box {
+				set_orientation: gtk::Orientation::Horizontal,
+				set_homogeneous: true,
+				set_spacing: 8,
+				gtk::Box {
+					set_orientation: gtk::Orientation::Vertical,
+					gtk::Label {
+						set_css_classes: &["heading", "mb-2"],
+						set_label: "Characters",
+					},
+					gtk::ScrolledWindow {
+						set_vexpand: true,
+						set_min_content_height: 240,
+						set_hscrollbar_policy: gtk::PolicyType::Never,
+						#[local_ref]
+						character_list -> gtk::ListBox {
+							set_css_classes: &["boxed-list"],
+							connect_row_activated[sender] => move |_, row| {
+								sender.input(Message::SelectCharacter(row.index()));
+							},
+						},
+					},
+				},
+				gtk::Box {
+					set_orientation: gtk::Orientation::Vertical,
+					gtk::Label {
+						set_css_classes: &["heading", "mb-2"],
+						#[watch]
+						set_label: &model.selected.map_or_else(
+							|| "Components".to_owned(),
+							|c| format!("Components of {c}"),
+						),
+					},
+					gtk::ScrolledWindow {
+						set_vexpand: true,
+						set_hscrollbar_policy: gtk::PolicyType::Never,
+						#[local_ref]
+						component_list -> gtk::ListBox {
+							set_css_classes: &["boxed-list"],
+							connect_row_activated[sender] => move |_, row| {
+								sender.input(Message::SelectComponent(row.index()));
+							},
+						},
+					},
+				},
+				gtk::Box {
+					set_orientation: gtk::Orientation::Vertical,
+					gtk::Label {
+						set_css_classes: &["heading", "mb-2"],
+						#[watch]
+						set_label: &model.component.map_or_else(
+							|| "Sharing a Component".to_owned(),
+							|c| format!("Your Characters with {c}"),
+						),
+					},
+					gtk::ScrolledWindow {
+						set_vexpand: true,
+						set_hscrollbar_policy: gtk::PolicyType::Never,
+						#[local_ref]
+						sharing_list -> gtk::ListBox {
+							set_css_classes: &["boxed-list"],
+							connect_row_activated[sender] => move |_, row| {
+								sender.input(Message::SelectSharing(row.index()));
+							},
+						},
+					},
+				},
+			},
+			gtk::Box {
+				set_css_classes: &["mt-8"],
+				set_homogeneous: true,
+				gtk::Button {
+					set_css_classes: &["suggested-action", "pill", "mx-2"],
+					set_label: "Back",
+					connect_clicked[sender] => move |_| {
+						sender.output(OutputMessage::Back).expect("Shouldn't fail");
+					}
+				},
+			},
+		}
+	}
+
+	async fn init(
+		ids: Self::Init,
+		widgets: Self::Root,
+		sender: AsyncComponentSender<Self>,
+	) -> AsyncComponentParts<Self> {
+		let model = Self {
+			ids,
+			set: Vec::new(),
+			known: HashSet::new(),
+			components: Vec::new(),
+			sharing: Vec::new(),
+			selected: None,
+			component: None,
+			character_list: gtk::ListBox::new(),
+			component_list: gtk::ListBox::new(),
+			sharing_list: gtk::ListBox::new(),
+		};
+
+		let character_list = &model.character_list;
+		let component_list = &model.component_list;
+		let sharing_list = &model.sharing_list;
+
+		let widgets = view_output!();
+
+		AsyncComponentParts { model, widgets }
+	}
+
+	async fn update(&mut self, message: Self::Input, _sender: AsyncComponentSender<Self>) {
+		match message {
+			Message::Show(set, known) => {
+				self.set = set;
+				self.known = known;
+				self.list_characters();
+				self.select(None);
+			}
+			Message::SelectCharacter(row) => {
+				let c = usize::try_from(row)
+					.ok()
+					.and_then(|index| self.set.get(index))
+					.copied();
+				self.select(c);
+			}
+			Message::SelectComponent(row) => {
+				let component = usize::try_from(row)
+					.ok()
+					.and_then(|index| self.components.get(index))
+					.copied();
+				self.select_component(component);
+			}
+			Message::SelectSharing(row) => {
+				let c = usize::try_from(row)
+					.ok()
+					.and_then(|index| self.sharing.get(index))
+					.copied();
+				if let Some(index) = c.and_then(|c| self.set.iter().position(|other| *other == c)) {
+					let index = i32::try_from(index).expect("sets shouldn't be that big");
+					self.character_list
+						.select_row(self.character_list.row_at_index(index).as_ref());
+				}
+				self.select(c);
+			}
+		}
+	}
+}
+
+impl ComponentsScreen {
+	fn list_characters(&self) {
+		clear(&self.character_list);
+		for c in &self.set {
+			let components = self.ids.components(*c);
+			let known_components = components
+				.iter()
+				.filter(|component| self.known.contains(component))
+				.count();
+			let split = if components.is_empty() {
+				"Can't be split further".to_owned()
+			} else {
+				format!(
+					"{known_components} of {} components known",
+					components.len()
+				)
+			};
+			let row = adw::ActionRow::builder()
+				.title(c.to_string())
+				.subtitle(format!("{} · {split}", self.known_label(*c)))
+				.activatable(true)
+				.build();
+			self.character_list.append(&row);
+		}
+	}
+
+	/// Shows the components of `c`, or nothing.
+	fn select(&mut self, c: Option<char>) {
+		self.selected = c;
+		self.components = c.map(|c| self.ids.components(c)).unwrap_or_default();
+		clear(&self.component_list);
+		for component in &self.components {
+			let sharing = self.ids.sharing(*component, &self.set).len();
+			let row = adw::ActionRow::builder()
+				.title(component.to_string())
+				.subtitle(format!(
+					"{} · In {sharing} of your characters",
+					self.known_label(*component)
+				))
+				.activatable(true)
+				.build();
+			self.component_list.append(&row);
+		}
+		self.select_component(None);
+	}
+
+	/// Lists the characters of the set that have `component` in them, or nothing.
+	fn select_component(&mut self, component: Option<char>) {
+		self.component = component;
+		self.sharing = component
+			.map(|component| self.ids.sharing(component, &self.set))
+			.unwrap_or_default();
+		clear(&self.sharing_list);
+		for c in &self.sharing {
+			let row = adw::ActionRow::builder()
+				.title(c.to_string())
+				.subtitle(self.known_label(*c))
+				.activatable(true)
+				.build();
+			self.sharing_list.append(&row);
+		}
+	}
+
+	fn known_label(&self, c: char) -> &'static str {
+		if self.known.contains(&c) {
+			"Known"
+		} else {
+			"Not known yet"
+		}
+	}
+}
+
+fn intro(ids: &Ids) -> &'static str {
+	if ids.is_empty() {
+		"No component data. Put cjkvi-ids' ids.txt or CHISE's IDS-UCS files in the ids folder of the data directory."
+	} else {
+		"Pick a character to see what it's made of, then a component to see which of your characters share it."
+	}
+}
+
+fn clear(list: &gtk::ListBox) {
+	while let Some(row) = list.first_child() {
+		list.remove(&row);
+	}
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+	/// The characters to browse and the ones to count as known.
+	Show(Vec<char>, HashSet<char>),
+	/// By row in each list.
+	SelectCharacter(i32),
+	SelectComponent(i32),
+	SelectSharing(i32),
+}
+
+#[derive(Debug, Clone)]
+pub enum OutputMessage {
+	Back,
+}
@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use lib::coverage::Coverage;
+use lib::extract::normalize;
+use lib::import::read_text;
+use relm4::adw::prelude::*;
+use relm4::component;
+use relm4::gtk;
+use relm4::prelude::*;
+use tracing::warn;
+
+use crate::input::show_import_error;
+
+/// How many of the unknown characters to list.
+const MAX_UNKNOWN_ROWS: usize = 100;
+
+#[derive(Debug)]
+pub struct CoverageScreen {
+	buf: gtk::TextBuffer,
+	unknown_tag: gtk::TextTag,
+	known: HashSet<char>,
+	coverage: Coverage,
+	unknown_list: gtk::ListBox,
+}
+
+#[component(pub, async)]
+impl SimpleAsyncComponent for CoverageScreen {
+	type Init = ();
+	type Input = Message;
+	type Output = OutputMessage;
+
+	view! {
+		#[root]
+		gtk::Box {
+			set_orientation: gtk::Orientation::Vertical,
+			set_hexpand: true,
+			gtk::Label {
+				set_css_classes: &["heading"],
+				set_label: "Reading Coverage",
+			},
+			gtk::Label {
+				set_css_classes: &["mb-2"],
+				set_label: "Paste or import a text to see how much of it you can read. The ones in red are the ones you don't know.",
+			},
+			gtk::Box {
+				set_orientation: gtk::Orientation::Horizontal,
+				gtk::Frame {
+					gtk::ScrolledWindow {
+						set_hexpand: true,
+						set_vexpand: true,
+						set_width_request: 416,
+						set_height_request: 180,
+						gtk::TextView {
+							set_css_classes: &["m-2"],
+							set_hexpand: true,
+							set_wrap_mode: gtk::WrapMode::WordChar,
+							set_buffer: Some(&model.buf),
+						},
+					}
+				},
+				gtk::Box {
+					set_css_classes: &["ml-2"],
+					set_orientation: gtk::Orientation::Vertical,
+					set_width_request: 260,
+					gtk::Label {
+						set_css_classes: &["title-2"],
+						#[watch]
+						set_label: &format!("{:.1}%", model.coverage.token_coverage() * 100.0),
+					},
+					gtk::Label {
+						#[watch]
+						set_label: &format!(
+							"of the text: {} of {} characters",
+							model.coverage.known_tokens(),
+							model.coverage.tokens()
+						),
+					},
+					gtk::Label {
+						set_css_classes: &["mb-2"],
+						#[watch]
+						set_label: &format!(
+							"{:.1}% of the distinct characters: {} of {}",
+							model.coverage.type_coverage() * 100.0,
+							model.coverage.known_types(),
+							model.coverage.types()
+						),
+					},
+					gtk::ScrolledWindow {
+						set_vexpand: true,
+						set_hscrollbar_policy: gtk::PolicyType::Never,
+						#[local_ref]
+						unknown_list -> gtk::ListBox {
+							set_css_classes: &["boxed-list"],
+							set_selection_mode: gtk::SelectionMode::None,
+						},
+					},
+				},
+			},
+			gtk::Box {
+				set_css_classes: &["mt-8"],
+				set_homogeneous: true,
+				gtk::Button {
+					set_css_classes: &["pill", "mx-2"],
+					set_label: "Import…",
+					connect_clicked[sender] => move |button| {
+						let dialog = gtk::FileDialog::builder()
+							.title("Import Text")
+							.modal(true)
+							.build();
+						let sender = sender.clone();
+						dialog.open(
+							button.root().and_downcast_ref::<gtk::Window>(),
+							gtk::gio::Cancellable::NONE,
+							move |file| {
+								if let Some(path) = file.ok().and_then(|file| file.path()) {
+									sender.input(Message::Import(path));
+								}
+							},
+						);
+					}
+				},
+				gtk::Button {
+					set_css_classes: &["suggested-action", "pill", "mx-2"],
+					set_label: "Back",
+					connect_clicked[sender] => move |_| {
+						sender.output(OutputMessage::Back).expect("Shouldn't fail");
+					}
+				},
+			},
+		}
+	}
+
+	async fn init(
+		_init: Self::Init,
+		widgets: Self::Root,
+		sender: AsyncComponentSender<Self>,
+	) -> AsyncComponentParts<Self> {
+		let tag_table = Rc::new(gtk::TextTagTable::new());
+		let buf = gtk::TextBuffer::new(Some(&tag_table));
+		let unknown_tag = buf
+			.create_tag(None, &[("foreground", &"red")])
+			.expect("fails if property doesn't exist or is not writeable");
+		let analyze_sender = sender.clone();
+		buf.connect_end_user_action(move |_| analyze_sender.input(Message::Analyze));
+
+		let known = HashSet::new();
+		let model = Self {
+			coverage: Coverage::analyze("", &known),
+			buf,
+			unknown_tag,
+			known,
+			unknown_list: gtk::ListBox::new(),
+		};
+
+		let unknown_list = &model.unknown_list;
+
+		let widgets = view_output!();
+
+		AsyncComponentParts { model, widgets }
+	}
+
+	async fn update(&mut self, message: Self::Input, sender: AsyncComponentSender<Self>) {
+		match message {
+			Message::SetKnown(known) => {
+				self.known = known;
+				sender.input(Message::Analyze);
+			}
+			Message::Import(path) => match read_text(&path) {
+				Ok(text) => {
+					self.buf.set_text(&text);
+					sender.input(Message::Analyze);
+				}
+				Err(e) => {
+					warn!("Failed to import {}: {e}", path.display());
+					show_import_error(&self.unknown_list, &e);
+				}
+			},
+			Message::Analyze => {
+				let (start, end) = self.buf.bounds();
+				let text = self.buf.text(&start, &end, true);
+				self.coverage = Coverage::analyze(&text, &self.known);
+				self.highlight_unknown(&text);
+				self.list_unknown();
+			}
+		}
+	}
+}
+
+impl CoverageScreen {
+	fn highlight_unknown(&self, text: &str) {
+		let (start, end) = self.buf.bounds();
+		self.buf.remove_tag(&self.unknown_tag, &start, &end);
+		let unknown: HashSet<char> = self.coverage.unknown().iter().map(|(c, _)| *c).collect();
+		for (offset, c) in text.chars().enumerate() {
+			// Compatibility ideographs are counted as the unified ideograph they stand for.
+			if !unknown.contains(&normalize(c)) {
+				continue;
+			}
+			let Ok(offset) = i32::try_from(offset) else {
+				break;
+			};
+			let start = self.buf.iter_at_offset(offset);
+			let end = self.buf.iter_at_offset(offset + 1);
+			self.buf.apply_tag(&self.unknown_tag, &start, &end);
+		}
+	}
+
+	/// Lists the unknown characters that would add the most coverage first.
+	fn list_unknown(&self) {
+		while let Some(row) = self.unknown_list.first_child() {
+			self.unknown_list.remove(&row);
+		}
+		for (c, occurrences) in self.coverage.unknown().iter().take(MAX_UNKNOWN_ROWS) {
+			let row = adw::ActionRow::builder()
+				.title(c.to_string())
+				.subtitle(format!(
+					"{occurrences} times, +{:.1}% of the text",
+					self.coverage.gain(*occurrences) * 100.0
+				))
+				.build();
+			self.unknown_list.append(&row);
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+	/// The characters to count as known, from the profile.
+	SetKnown(HashSet<char>),
+	Import(PathBuf),
+	Analyze,
+}
+
+#[derive(Debug, Clone)]
+pub enum OutputMessage {
+	Back,
+}
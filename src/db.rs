@@ -1,11 +1,13 @@
 use anyhow::Result;
-use lib::is_chinese_character;
+use lib::extract::extract;
+use lib::radical::RadicalIndex;
+use lib::variant::Variants;
 use sqlx::{migrate, query, sqlite::SqliteConnectOptions, QueryBuilder, SqlitePool};
 use tracing::debug;
 
 use rand::seq::SliceRandom;
 
-use crate::testing::Test;
+use crate::testing::{Recalled, Test};
 
 // const SCHEMA_SQL: &str = include_str!("schema.sql");
 // const SCHEMA_VERSION: i32 = 1;
@@ -13,6 +15,24 @@ use crate::testing::Test;
 // This is the default profile id to use until we support having multiple profiles.
 const DEFAULT_PROFILE_ID: u8 = 0;
 
+/// A named list of characters, kept apart from the unsaved list that presets write into.
+#[derive(Clone, Debug)]
+pub struct SavedList {
+	pub(crate) id: i64,
+	pub(crate) name: String,
+	pub(crate) description: String,
+	/// In UTC, formatted like `2025-02-04 17:24:33`.
+	pub(crate) created_at: String,
+}
+
+/// One answer from the history of a character.
+#[derive(Clone, Debug)]
+pub struct Answer {
+	pub(crate) recalled: Recalled,
+	/// In UTC, formatted like `2025-02-04 17:24:33`.
+	pub(crate) answered_at: String,
+}
+
 #[derive(Clone, Debug)]
 pub struct Db {
 	pool: SqlitePool,
@@ -70,7 +90,11 @@ impl Db {
 		Ok(exists)
 	}
 
+	/// The text of the current list.
 	pub async fn get_text(&self) -> Result<String> {
+		if let Some(id) = self.current_list().await? {
+			return self.get_list_text(id).await;
+		}
 		let mut conn = self.pool.acquire().await?;
 		let text = query!(
 			"SELECT text FROM user_profile_raw_test_text WHERE profile = ? LIMIT 1",
@@ -83,7 +107,14 @@ impl Db {
 	}
 
 	pub async fn set_text(&self, s: &str) -> Result<()> {
+		let current_list = self.current_list().await?;
 		let mut conn = self.pool.acquire().await?;
+		if let Some(id) = current_list {
+			query!("UPDATE saved_list SET text = ?1 WHERE id = ?2", s, id)
+				.execute(&mut *conn)
+				.await?;
+			return Ok(());
+		}
 		query!(
 			"UPDATE user_profile_raw_test_text SET text = ?1 WHERE profile = ?2",
 			s,
@@ -94,18 +125,32 @@ impl Db {
 		Ok(())
 	}
 
-	pub async fn create_test_from_raw_text(&self) -> Result<()> {
+	/// Replaces the test with the characters of the current list. With `variants`, only the
+	/// first character of every variant group is kept. With `radicals`, characters with the same
+	/// radical are tested one after another.
+	pub async fn create_test_from_raw_text(
+		&self,
+		shuffle: bool,
+		variants: Option<&Variants>,
+		radicals: Option<&RadicalIndex>,
+	) -> Result<()> {
 		let mut conn = self.pool.acquire().await?;
 
 		let mut chars = self.get_all_chinese_characters().await?;
+		if let Some(variants) = variants {
+			chars = variants.collapse(&chars);
+		}
 
-		chars.dedup(); // I'm not sure if this actually makes a difference, but I don't wanna see this bug.
-
-		let mut rng = rand::rng();
-		chars.shuffle(&mut rng);
+		if shuffle {
+			let mut rng = rand::rng();
+			chars.shuffle(&mut rng);
+		}
+		if let Some(radicals) = radicals {
+			radicals.group_by_radical(&mut chars);
+		}
 
 		query!(
-			"UPDATE user_profile SET test_exists = true WHERE id = ?",
+			"UPDATE user_profile SET test_exists = true, test_list = current_list WHERE id = ?",
 			DEFAULT_PROFILE_ID
 		)
 		.execute(&mut *conn)
@@ -130,10 +175,11 @@ impl Db {
 		Ok(())
 	}
 
+	/// The distinct characters of the current list, as [`extract`] finds them.
 	pub async fn get_all_chinese_characters(&self) -> Result<Vec<char>> {
 		let text = self.get_text().await?;
 		debug!("All the text in the database {text}");
-		Ok(text.chars().filter(is_chinese_character).collect())
+		Ok(extract(&text).into_chars())
 	}
 
 	pub async fn chinese_character_exists(&self) -> Result<bool> {
@@ -154,7 +200,7 @@ impl Db {
 	pub async fn get_default_profile_chars(&self) -> Result<Vec<Test>> {
 		let mut conn = self.pool.acquire().await?;
 		let records = query!(
-			"SELECT char, known FROM user_profile_characters WHERE profile = ?1",
+			"SELECT char, known FROM user_profile_characters WHERE profile = ?1 ORDER BY rowid",
 			DEFAULT_PROFILE_ID,
 		)
 		.fetch_all(&mut *conn)
@@ -183,6 +229,216 @@ impl Db {
 		Ok(chars)
 	}
 
+	pub async fn saved_lists(&self) -> Result<Vec<SavedList>> {
+		let mut conn = self.pool.acquire().await?;
+		let lists = query!(
+			"SELECT id, name, description, created_at FROM saved_list WHERE profile = ? ORDER BY id",
+			DEFAULT_PROFILE_ID,
+		)
+		.fetch_all(&mut *conn)
+		.await?
+		.into_iter()
+		.map(|record| SavedList {
+			id: record.id,
+			name: record.name,
+			description: record.description,
+			created_at: record.created_at,
+		})
+		.collect();
+		Ok(lists)
+	}
+
+	pub async fn get_list_text(&self, id: i64) -> Result<String> {
+		let mut conn = self.pool.acquire().await?;
+		let text = query!("SELECT text FROM saved_list WHERE id = ? LIMIT 1", id)
+			.fetch_one(&mut *conn)
+			.await?
+			.text;
+		Ok(text)
+	}
+
+	/// Returns the id of the new list.
+	pub async fn create_list(&self, name: &str, description: &str, text: &str) -> Result<i64> {
+		let mut conn = self.pool.acquire().await?;
+		let id = query!(
+			"INSERT INTO saved_list (profile, name, description, text) VALUES (?1, ?2, ?3, ?4)",
+			DEFAULT_PROFILE_ID,
+			name,
+			description,
+			text,
+		)
+		.execute(&mut *conn)
+		.await?
+		.last_insert_rowid();
+		Ok(id)
+	}
+
+	/// Returns the id of the copy.
+	pub async fn duplicate_list(&self, id: i64) -> Result<i64> {
+		let mut conn = self.pool.acquire().await?;
+		let id = query!(
+			"INSERT INTO saved_list (profile, name, description, text)
+			SELECT profile, name || ' (copy)', description, text FROM saved_list WHERE id = ?",
+			id,
+		)
+		.execute(&mut *conn)
+		.await?
+		.last_insert_rowid();
+		Ok(id)
+	}
+
+	pub async fn rename_list(&self, id: i64, name: &str, description: &str) -> Result<()> {
+		let mut conn = self.pool.acquire().await?;
+		query!(
+			"UPDATE saved_list SET name = ?1, description = ?2 WHERE id = ?3",
+			name,
+			description,
+			id,
+		)
+		.execute(&mut *conn)
+		.await?;
+		Ok(())
+	}
+
+	/// If it's the current list, the unsaved list becomes current again.
+	pub async fn delete_list(&self, id: i64) -> Result<()> {
+		let mut conn = self.pool.acquire().await?;
+		query!("DELETE FROM saved_list WHERE id = ?", id)
+			.execute(&mut *conn)
+			.await?;
+		Ok(())
+	}
+
+	/// The saved list being edited, or `None` for the unsaved list.
+	pub async fn current_list(&self) -> Result<Option<i64>> {
+		let mut conn = self.pool.acquire().await?;
+		let id = query!(
+			"SELECT current_list FROM user_profile WHERE id = ? LIMIT 1",
+			DEFAULT_PROFILE_ID,
+		)
+		.fetch_one(&mut *conn)
+		.await?
+		.current_list;
+		Ok(id)
+	}
+
+	pub async fn set_current_list(&self, id: Option<i64>) -> Result<()> {
+		let mut conn = self.pool.acquire().await?;
+		query!(
+			"UPDATE user_profile SET current_list = ?1 WHERE id = ?2",
+			id,
+			DEFAULT_PROFILE_ID,
+		)
+		.execute(&mut *conn)
+		.await?;
+		Ok(())
+	}
+
+	/// The name of the saved list the current test was created from.
+	pub async fn test_list_name(&self) -> Result<Option<String>> {
+		let mut conn = self.pool.acquire().await?;
+		let name = query!(
+			"SELECT saved_list.name FROM user_profile
+			JOIN saved_list ON saved_list.id = user_profile.test_list
+			WHERE user_profile.id = ? LIMIT 1",
+			DEFAULT_PROFILE_ID,
+		)
+		.fetch_optional(&mut *conn)
+		.await?
+		.map(|record| record.name);
+		Ok(name)
+	}
+
+	/// The characters of the current test answered with `recalled`, in test order. Characters
+	/// the test hasn't reached yet are left out.
+	pub async fn get_recalled_characters(&self, recalled: Recalled) -> Result<Vec<char>> {
+		let mut conn = self.pool.acquire().await?;
+		let known = recalled == Recalled::Known;
+		let records = query!(
+			"SELECT char FROM user_profile_characters WHERE profile = ?1 AND answered AND known = ?2 ORDER BY rowid",
+			DEFAULT_PROFILE_ID,
+			known,
+		)
+		.fetch_all(&mut *conn)
+		.await?;
+		Ok(records
+			.into_iter()
+			.filter_map(|record| u32::try_from(record.char).ok().and_then(char::from_u32))
+			.collect())
+	}
+
+	/// Saves the answer for `c` in the current test, which is what
+	/// [`Self::get_recalled_characters`] reads, and adds it to the history of `c`. Answering
+	/// again after going back replaces the earlier answer of this test in the history.
+	pub async fn record_answer(&self, c: char, recalled: Recalled) -> Result<()> {
+		let mut tx = self.pool.begin().await?;
+		let code = u32::from(c);
+		let known = recalled == Recalled::Known;
+		let answered = query!(
+			"SELECT answered FROM user_profile_characters WHERE profile = ?1 AND char = ?2",
+			DEFAULT_PROFILE_ID,
+			code,
+		)
+		.fetch_optional(&mut *tx)
+		.await?
+		.is_some_and(|record| record.answered);
+		query!(
+			"UPDATE user_profile_characters SET known = ?1, answered = true WHERE profile = ?2 AND char = ?3",
+			known,
+			DEFAULT_PROFILE_ID,
+			code,
+		)
+		.execute(&mut *tx)
+		.await?;
+		let replaced = answered
+			&& query!(
+				"UPDATE answer SET known = ?1, answered_at = CURRENT_TIMESTAMP WHERE id = (SELECT MAX(id) FROM answer WHERE profile = ?2 AND char = ?3)",
+				known,
+				DEFAULT_PROFILE_ID,
+				code,
+			)
+			.execute(&mut *tx)
+			.await?
+			.rows_affected()
+				> 0;
+		if !replaced {
+			query!(
+				"INSERT INTO answer (profile, char, known) VALUES (?1, ?2, ?3)",
+				DEFAULT_PROFILE_ID,
+				code,
+				known,
+			)
+			.execute(&mut *tx)
+			.await?;
+		}
+		tx.commit().await?;
+		Ok(())
+	}
+
+	/// Every answer given for `c`, newest first.
+	pub async fn answer_history(&self, c: char) -> Result<Vec<Answer>> {
+		let mut conn = self.pool.acquire().await?;
+		let code = u32::from(c);
+		let answers = query!(
+			"SELECT known, answered_at FROM answer WHERE profile = ?1 AND char = ?2 ORDER BY id DESC",
+			DEFAULT_PROFILE_ID,
+			code,
+		)
+		.fetch_all(&mut *conn)
+		.await?
+		.into_iter()
+		.map(|record| Answer {
+			recalled: if record.known {
+				Recalled::Known
+			} else {
+				Recalled::Unknown
+			},
+			answered_at: record.answered_at,
+		})
+		.collect();
+		Ok(answers)
+	}
+
 	pub async fn get_test_progress(&self) -> Result<i64> {
 		let mut conn = self.pool.acquire().await?;
 		let record = query!(
@@ -0,0 +1,299 @@
+use lib::is_chinese_character;
+use lib::load_kanjidic::{
+	Character, DicRef, Dict2, QCode, QueryCodeType, RadicalType, ReadingType, MAX_FREQ_RANK,
+};
+use lib::lookup::{CharInfo, Lookup};
+use lib::script::kangxi_radical;
+use lib::unihan::Unihan;
+use lib::variant::Variants;
+use relm4::adw::prelude::*;
+use relm4::{adw, gtk};
+
+use crate::db::Answer;
+use crate::testing::Recalled;
+
+/// How many of the latest answers to list.
+const MAX_ANSWERS: usize = 20;
+
+/// The Chinese character at `x`, `y` in `view`, for opening its details on a click.
+pub(crate) fn char_at(view: &gtk::TextView, x: f64, y: f64) -> Option<char> {
+	#[allow(
+		clippy::cast_possible_truncation,
+		clippy::as_conversions,
+		reason = "Widget coordinates are well within i32"
+	)]
+	let (x, y) = view.window_to_buffer_coords(gtk::TextWindowType::Widget, x as i32, y as i32);
+	let c = view.iter_at_location(x, y)?.char();
+	is_chinese_character(&c).then_some(c)
+}
+
+/// Shows everything KANJIDIC2 and Unihan know about `c`, with the answers given for it.
+pub fn present(
+	parent: &impl IsA<gtk::Widget>,
+	dict: &Dict2,
+	unihan: &Unihan,
+	variants: &Variants,
+	c: char,
+	history: &[Answer],
+) {
+	let page = adw::PreferencesPage::new();
+	let info = Lookup::new(dict).with_unihan(unihan).get(c);
+	if info.is_empty() {
+		let group = adw::PreferencesGroup::builder()
+			.description("This character isn't in KANJIDIC2 or Unihan.")
+			.build();
+		page.add(&group);
+	} else {
+		page.add(&readings_group(&info));
+		page.add(&meanings_group(&info));
+		page.add(&details_group(dict, &info));
+		if let Some(character) = info.kanjidic() {
+			page.add(&codes_group(character));
+		}
+	}
+	page.add(&variants_group(variants, c));
+	page.add(&history_group(history));
+
+	let literal = gtk::Label::builder()
+		.css_classes(["text-5xl", "m-2"])
+		.label(c.to_string())
+		.selectable(true)
+		.build();
+	let content = gtk::Box::new(gtk::Orientation::Vertical, 0);
+	content.append(&literal);
+	content.append(&page);
+	page.set_vexpand(true);
+
+	let toolbar = adw::ToolbarView::new();
+	toolbar.add_top_bar(&adw::HeaderBar::new());
+	toolbar.set_content(Some(&content));
+	let dialog = adw::Dialog::builder()
+		.title(format!("Details of {c}"))
+		.content_width(480)
+		.content_height(640)
+		.child(&toolbar)
+		.build();
+	dialog.present(Some(parent));
+}
+
+/// A row with a label above its value.
+fn property_row(title: &str, value: &str) -> adw::ActionRow {
+	adw::ActionRow::builder()
+		.css_classes(["property"])
+		.title(title)
+		.subtitle(value)
+		.subtitle_selectable(true)
+		.build()
+}
+
+/// Every reading, one row per language, then the other readings KANJIDIC2 lists and the ones
+/// used in names.
+fn readings_group(info: &CharInfo) -> adw::PreferencesGroup {
+	let group = adw::PreferencesGroup::builder().title("Readings").build();
+	let by_language = [
+		("On'yomi", info.on()),
+		("Kun'yomi", info.kun()),
+		("Mandarin", info.mandarin()),
+		("Cantonese", info.cantonese()),
+		("Korean", info.hangul()),
+		("Korean (romanized)", info.korean_romanized()),
+		("Vietnamese", info.vietnamese()),
+	];
+	for (language, values) in by_language {
+		if !values.is_empty() {
+			group.add(&property_row(language, &values.join("、")));
+		}
+	}
+	let Some(character) = info.kanjidic() else {
+		return group;
+	};
+	let mut other: Vec<(&str, Vec<&str>)> = Vec::new();
+	for reading in character.readings() {
+		let ReadingType::Other(r_type) = reading.r_type() else {
+			continue;
+		};
+		match other
+			.iter_mut()
+			.find(|(label, _)| *label == r_type.as_str())
+		{
+			Some((_, values)) => values.push(reading.value()),
+			None => other.push((r_type.as_str(), vec![reading.value()])),
+		}
+	}
+	for (r_type, values) in other {
+		group.add(&property_row(r_type, &values.join("、")));
+	}
+	if !character.nanori().is_empty() {
+		group.add(&property_row(
+			"Nanori (in names)",
+			&character.nanori().join("、"),
+		));
+	}
+	group
+}
+
+/// English first, from Unihan's definition when KANJIDIC2 has none, then one row per other
+/// language in the order KANJIDIC2 lists them.
+fn meanings_group(info: &CharInfo) -> adw::PreferencesGroup {
+	let group = adw::PreferencesGroup::builder().title("Meanings").build();
+	let english = info.meanings();
+	if !english.is_empty() {
+		group.add(&property_row("English", &english.join("; ")));
+	}
+	let mut by_lang: Vec<(&str, Vec<&str>)> = Vec::new();
+	for meaning in info.kanjidic().into_iter().flat_map(Character::meanings) {
+		if meaning.lang() == "en" {
+			continue;
+		}
+		match by_lang.iter_mut().find(|(lang, _)| *lang == meaning.lang()) {
+			Some((_, values)) => values.push(meaning.value()),
+			None => by_lang.push((meaning.lang(), vec![meaning.value()])),
+		}
+	}
+	for (lang, values) in by_lang {
+		let language = match lang {
+			"fr" => "French",
+			"es" => "Spanish",
+			"pt" => "Portuguese",
+			other => other,
+		};
+		group.add(&property_row(language, &values.join("; ")));
+	}
+	group
+}
+
+fn details_group(dict: &Dict2, info: &CharInfo) -> adw::PreferencesGroup {
+	let group = adw::PreferencesGroup::builder().title("Details").build();
+	if let Some(strokes) = info.stroke_count() {
+		group.add(&property_row("Strokes", &strokes.to_string()));
+	}
+	if let Some(number) = info.radical() {
+		let mut radical = number.to_string();
+		if let Some(kangxi) = kangxi_radical(number) {
+			radical = format!("{kangxi} {radical}");
+			let names = dict
+				.get(kangxi)
+				.map(Character::radical_names)
+				.unwrap_or_default();
+			if !names.is_empty() {
+				radical = format!("{radical} · {}", names.join(", "));
+			}
+		}
+		if let Some(nelson) = info
+			.kanjidic()
+			.and_then(|character| character.radical(&RadicalType::NelsonC))
+			.filter(|nelson| *nelson != number)
+		{
+			radical = format!("{radical} (Nelson {nelson})");
+		}
+		group.add(&property_row("Radical", &radical));
+	}
+	let Some(character) = info.kanjidic() else {
+		return group;
+	};
+	if let Some(grade) = character.grade() {
+		let grade = match grade {
+			1..=6 => format!("Grade {grade} (Kyōiku)"),
+			8 => "Secondary school (Jōyō)".to_owned(),
+			9 | 10 => "Jinmeiyō".to_owned(),
+			other => other.to_string(),
+		};
+		group.add(&property_row("Grade", &grade));
+	}
+	if let Some(jlpt) = character.jlpt() {
+		group.add(&property_row("JLPT", &format!("Level {jlpt} (old test)")));
+	}
+	if let Some(freq) = character.freq() {
+		group.add(&property_row(
+			"Frequency",
+			&format!("{freq} of the {MAX_FREQ_RANK} most used in newspapers"),
+		));
+	}
+	group
+}
+
+/// Dictionary index numbers and lookup codes, folded away since they're only useful with the
+/// books and methods they come from.
+fn codes_group(character: &Character) -> adw::PreferencesGroup {
+	let group = adw::PreferencesGroup::new();
+	let dic_refs = adw::ExpanderRow::builder()
+		.title("Dictionary index numbers")
+		.build();
+	for dic_ref in character.dic_refs() {
+		dic_refs.add_row(&property_row(
+			&dic_ref.dr_type().to_string(),
+			&dic_ref_text(dic_ref),
+		));
+	}
+	let codes = adw::ExpanderRow::builder().title("Query codes").build();
+	for code in character.query_codes() {
+		codes.add_row(&property_row(&query_code_label(code), code.value()));
+	}
+	if character.dic_refs().next().is_some() {
+		group.add(&dic_refs);
+	}
+	if character.query_codes().next().is_some() {
+		group.add(&codes);
+	}
+	group
+}
+
+fn dic_ref_text(dic_ref: &DicRef) -> String {
+	dic_ref.volume_page().map_or_else(
+		|| dic_ref.value().to_owned(),
+		|(volume, page)| format!("{} (volume {volume}, page {page})", dic_ref.value()),
+	)
+}
+
+fn query_code_label(code: &QCode) -> String {
+	let label = match code.qc_type() {
+		QueryCodeType::Skip => "SKIP".to_owned(),
+		QueryCodeType::ShDesc => "Spahn & Hadamitzky".to_owned(),
+		QueryCodeType::FourCorner => "Four corner".to_owned(),
+		QueryCodeType::Deroo => "De Roo".to_owned(),
+		QueryCodeType::Misclass => "Misclassification".to_owned(),
+		QueryCodeType::Other(other) => other.clone(),
+	};
+	match code.skip_misclass() {
+		Some(misclass) => format!("{label}, commonly mistaken by {misclass}"),
+		None => label,
+	}
+}
+
+fn variants_group(variants: &Variants, c: char) -> adw::PreferencesGroup {
+	let group = adw::PreferencesGroup::builder().title("Variants").build();
+	let of = variants.of(c);
+	if of.is_empty() {
+		group.set_description(Some("No known variants."));
+	}
+	for (kind, variant) in of {
+		group.add(&property_row(kind.label(), &variant.to_string()));
+	}
+	group
+}
+
+fn history_group(history: &[Answer]) -> adw::PreferencesGroup {
+	let known = history
+		.iter()
+		.filter(|answer| answer.recalled == Recalled::Known)
+		.count();
+	let description = match history.len() {
+		0 => "Not answered in a test yet.".to_owned(),
+		total => format!("Known {known} of {total} times."),
+	};
+	let group = adw::PreferencesGroup::builder()
+		.title("Your Answers")
+		.description(description)
+		.build();
+	for answer in history.iter().take(MAX_ANSWERS) {
+		let row = adw::ActionRow::builder()
+			.title(match answer.recalled {
+				Recalled::Known => "Known",
+				Recalled::Unknown => "Not known",
+			})
+			.subtitle(&answer.answered_at)
+			.build();
+		group.add(&row);
+	}
+	group
+}
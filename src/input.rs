@@ -1,42 +1,126 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::error::Error as _;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use lib::is_chinese_character;
-use lib::load_kanjidic::bootstrap_dict;
+use lib::extract::{extract, Extraction};
+use lib::ids::Ids;
+use lib::import::{self, count_characters, read_text};
 use lib::load_kanjidic::Dict2;
+use lib::load_kanjidic::MAX_FREQ_RANK;
+use lib::preset::{Preset, PresetRegistry};
+use lib::radical::RadicalIndex;
+use lib::recommend::{Recommendation, Recommender, Weights};
+use lib::search::SearchIndex;
+use lib::set_op::SetOp;
+use lib::unihan::Unihan;
+use lib::variant::Variants;
 use relm4::adw::prelude::*;
 use relm4::component;
 use relm4::gtk;
 use relm4::prelude::*;
-use tracing::debug;
+use tracing::{debug, warn};
 
-use crate::db::Db;
-use crate::testing::Test;
+use crate::db::{Db, SavedList};
+use crate::detail::char_at;
+use crate::search;
+use crate::testing::{Recalled, Test};
 
 #[derive(Debug, Clone)]
 pub enum Message {
 	UpdateText(String),
+	/// Switches to the unsaved list and replaces its text, so saved lists are never overwritten.
+	Replace(String),
+	/// By row in the sidebar, where the first row is the unsaved list.
+	SelectList(i32),
+	NewList,
+	DuplicateList,
+	/// Asks for a new name and description.
+	RenameList,
+	SetListDetails(i64, String, String),
+	DeleteList,
+	/// Index into the [`PresetRegistry`].
+	UsePreset(usize),
+	UseMostFrequent(u64),
+	SetKeepOrder(bool),
+	SetCollapseVariants(bool),
+	SetGroupByRadical(bool),
+	/// Replaces the list with the characters in a document.
+	Import(PathBuf),
+	SetImportByOccurrence(bool),
+	/// Replaces the list with the result of `op` on two [`ListSource`]s, by drop-down position.
+	Combine(u32, SetOp, u32),
+	/// Recommends a batch of this many characters to learn next.
+	Recommend(usize, Weights, BatchTarget),
+	BrowseComponents,
+	/// Opens the character search.
+	Search,
+	/// Adds a character from the search to the end of the list.
+	AddCharacter(char),
 	NewTest,
 }
 
+/// What to make of a batch of recommended characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchTarget {
+	/// A new saved list.
+	List,
+	/// A new test, through the unsaved list.
+	Test,
+}
+
 #[derive(Debug, Clone)]
 pub enum OutputMessage {
 	ResumeTest,
 	NewTest(Vec<Test>),
+	/// Browses the components of the characters in the list.
+	BrowseComponents(Vec<char>),
+	ShowCharacter(char),
 }
 
 #[derive(Debug)]
 pub struct InputScreen {
-	dict: Dict2,
+	dict: Arc<Dict2>,
+	presets: Arc<PresetRegistry>,
+	variants: Arc<Variants>,
+	unihan: Arc<Unihan>,
+	ids: Arc<Ids>,
+	radicals: Arc<RadicalIndex>,
+	search: Arc<SearchIndex>,
 	db: Db,
 	text: String,
 	test_exists: bool,
 	chinese_character_exists: bool,
+	/// Test the characters in the order of the list instead of shuffling them.
+	keep_order: bool,
+	/// Test only the first of a group of variants, like 学 and 學.
+	collapse_variants: bool,
+	/// Test characters with the same radical one after another.
+	group_by_radical: bool,
+	/// Put the characters of an imported document in order of how often they occur.
+	import_by_occurrence: bool,
+	lists: Vec<SavedList>,
+	/// The saved list being edited, or `None` for the unsaved one.
+	current_list: Option<i64>,
+	/// The name of the saved list the current test was created from.
+	test_list: Option<String>,
+	list_box: gtk::ListBox,
+	/// Names of every [`ListSource`], shown in the combine drop-downs.
+	source_names: gtk::StringList,
 }
 
 #[component(pub, async)]
 impl SimpleAsyncComponent for InputScreen {
-	type Init = (Db, Arc<PathBuf>);
+	type Init = (
+		Db,
+		Arc<Dict2>,
+		Arc<PresetRegistry>,
+		Arc<Variants>,
+		Arc<Unihan>,
+		Arc<Ids>,
+		Arc<RadicalIndex>,
+	);
 	type Input = Message;
 	type Output = OutputMessage;
 
@@ -45,9 +129,61 @@ impl SimpleAsyncComponent for InputScreen {
 		gtk::Box {
 			set_orientation: gtk::Orientation::Vertical,
 			set_hexpand: true,
+			#[name = "input_card"]
 			gtk::Box {
 				set_css_classes: &["card", "p-2"],
 				set_orientation: gtk::Orientation::Horizontal,
+				gtk::Box {
+					set_css_classes: &["mr-2"],
+					set_orientation: gtk::Orientation::Vertical,
+					set_width_request: 200,
+					gtk::Label {
+						set_css_classes: &["heading"],
+						set_label: "Lists",
+					},
+					gtk::ScrolledWindow {
+						set_css_classes: &["my-2"],
+						set_vexpand: true,
+						set_hscrollbar_policy: gtk::PolicyType::Never,
+						#[local_ref]
+						list_box -> gtk::ListBox {
+							set_css_classes: &["navigation-sidebar"],
+							connect_row_activated[sender] => move |_, row| {
+								sender.input(Message::SelectList(row.index()));
+							},
+						},
+					},
+					gtk::Box {
+						set_css_classes: &["linked"],
+						set_halign: gtk::Align::Center,
+						gtk::Button {
+							set_icon_name: "list-add-symbolic",
+							set_tooltip: "Save as a new list",
+							connect_clicked => Message::NewList,
+						},
+						gtk::Button {
+							set_icon_name: "edit-copy-symbolic",
+							set_tooltip: "Duplicate",
+							#[watch]
+							set_sensitive: model.current_list.is_some(),
+							connect_clicked => Message::DuplicateList,
+						},
+						gtk::Button {
+							set_icon_name: "document-edit-symbolic",
+							set_tooltip: "Rename",
+							#[watch]
+							set_sensitive: model.current_list.is_some(),
+							connect_clicked => Message::RenameList,
+						},
+						gtk::Button {
+							set_icon_name: "user-trash-symbolic",
+							set_tooltip: "Delete",
+							#[watch]
+							set_sensitive: model.current_list.is_some(),
+							connect_clicked => Message::DeleteList,
+						},
+					},
+				},
 				gtk::Box {
 					set_orientation: gtk::Orientation::Vertical,
 					gtk::Label {
@@ -67,6 +203,18 @@ impl SimpleAsyncComponent for InputScreen {
 							set_hexpand: true,
 							set_vexpand: true,
 							set_wrap_mode: gtk::WrapMode::WordChar,
+							set_tooltip_text: Some("Double-click a character to see its details"),
+							add_controller = gtk::GestureClick {
+								connect_released[sender] => move |gesture, n_press, x, y| {
+									if n_press != 2 {
+										return;
+									}
+									let view = gesture.widget().and_downcast::<gtk::TextView>();
+									if let Some(c) = view.and_then(|view| char_at(&view, x, y)) {
+										sender.output(OutputMessage::ShowCharacter(c)).expect("This shouldn't fail");
+									}
+								},
+							},
 							#[wrap(Some)]
 							set_buffer: buf = &gtk::TextBuffer {
 								#[watch]
@@ -81,11 +229,54 @@ impl SimpleAsyncComponent for InputScreen {
 					gtk::Label {
 						set_css_classes:  &["m-2"],
 						#[watch]
-						set_label: &format!("{} Characters", {
+						set_label: &{
 							#[allow(clippy::needless_borrow, reason = "This is a bug in clippy, the type system demands a reference")]
 							let text = get_full_text_from_buffer(&buf);
-							text.trim().chars().filter(is_chinese_character).count()
-						}),
+							counter_label(&extract(&text))
+						},
+					},
+					gtk::Box {
+						set_css_classes: &["m-2"],
+						set_spacing: 8,
+						gtk::Button {
+							set_label: "Import…",
+							set_tooltip: "Text, HTML, EPUB or subtitle files. You can also drop them here.",
+							connect_clicked[sender] => move |button| {
+								let dialog = gtk::FileDialog::builder()
+									.title("Import Characters")
+									.modal(true)
+									.build();
+								let sender = sender.clone();
+								dialog.open(
+									button.root().and_downcast_ref::<gtk::Window>(),
+									gtk::gio::Cancellable::NONE,
+									move |file| {
+										if let Some(path) = file.ok().and_then(|file| file.path()) {
+											sender.input(Message::Import(path));
+										}
+									},
+								);
+							}
+						},
+						gtk::Button {
+							set_label: "Search…",
+							set_tooltip: "Look characters up by reading, meaning, stroke count, radical, SKIP or four-corner code",
+							connect_clicked => Message::Search,
+						},
+						gtk::Button {
+							set_label: "Components",
+							set_tooltip: "See which components of these characters you know",
+							#[watch]
+							set_sensitive: model.chinese_character_exists,
+							connect_clicked => Message::BrowseComponents,
+						},
+						gtk::CheckButton {
+							set_label: Some("Most frequent first"),
+							set_active: model.import_by_occurrence,
+							connect_toggled[sender] => move |check| {
+								sender.input(Message::SetImportByOccurrence(check.is_active()));
+							}
+						},
 					},
 				},
 				adw::PreferencesGroup {
@@ -93,40 +284,173 @@ impl SimpleAsyncComponent for InputScreen {
 					set_width_request: 320,
 					set_hexpand: false,
 					set_css_classes: &["card", "boxed-list-separate", "p-4", "view", "ml-2"],
-					gtk::Button {
-						set_css_classes: &["my-2", "py-2"],
-						set_label: "한문 교육용 기초 한자 1800",
-						connect_clicked => {
-							Message::UpdateText(include_str!("hanmun_hanja_1800.txt").to_string())
-						},
+					#[name = "preset_search"]
+					gtk::SearchEntry {
+						set_css_classes: &["my-2"],
+						set_placeholder_text: Some("Search presets"),
 					},
-					gtk::Button {
-						set_css_classes: &["my-2", "py-2"],
-						set_label: "日本語能力試験",
-						connect_clicked => {
-							Message::UpdateText(jlpt.clone())
+					gtk::ScrolledWindow {
+						set_css_classes: &["my-2"],
+						set_min_content_height: 240,
+						set_vexpand: true,
+						set_hscrollbar_policy: gtk::PolicyType::Never,
+						#[name = "preset_list"]
+						gtk::ListBox {
+							set_css_classes: &["boxed-list"],
+							set_selection_mode: gtk::SelectionMode::None,
+							connect_row_activated[sender] => move |_, row| {
+								if let Ok(index) = usize::try_from(row.index()) {
+									sender.input(Message::UsePreset(index));
+								}
+							},
 						},
 					},
-					gtk::Button {
-						set_css_classes: &["my-2", "py-2"],
-						set_label: "常用漢字",
-						connect_clicked => {
-							Message::UpdateText(joyo.clone())
+					#[name = "most_frequent"]
+					adw::SpinRow::with_range(1.0, max_freq_rank, 50.0) {
+						set_css_classes: &["my-2"],
+						set_title: "新聞の頻出漢字",
+						set_subtitle: "Most frequent first",
+						set_value: 500.0,
+						add_suffix = &gtk::Button {
+							set_valign: gtk::Align::Center,
+							set_label: "Use",
+							connect_clicked[sender, most_frequent] => move |_| {
+								#[allow(
+									clippy::cast_possible_truncation,
+									clippy::cast_sign_loss,
+									clippy::as_conversions,
+									reason = "The spin row only holds whole numbers from 1 to MAX_FREQ_RANK"
+								)]
+								let n = most_frequent.value() as u64;
+								sender.input(Message::UseMostFrequent(n));
+							}
 						},
 					},
-					gtk::Button {
-						set_css_classes: &["my-2", "py-2"],
-						set_label: "教育漢字",
-						connect_clicked => {
-							Message::UpdateText(kyoiku.clone())
+					adw::ExpanderRow {
+						set_css_classes: &["my-2"],
+						set_title: "Combine Lists",
+						set_subtitle: "Union, intersection or difference",
+						add_row = &gtk::Box {
+							set_css_classes: &["p-2"],
+							set_orientation: gtk::Orientation::Vertical,
+							set_spacing: 6,
+							#[name = "combine_left"]
+							gtk::DropDown {
+								set_hexpand: true,
+								set_model: Some(&model.source_names),
+							},
+							#[name = "combine_op"]
+							gtk::DropDown::from_strings(&op_names) {
+								set_hexpand: true,
+								set_selected: 2,
+							},
+							#[name = "combine_right"]
+							gtk::DropDown {
+								set_hexpand: true,
+								set_model: Some(&model.source_names),
+								set_selected: 1,
+							},
+							gtk::Button {
+								set_label: "Use",
+								connect_clicked[sender, combine_left, combine_op, combine_right] => move |_| {
+									let op = usize::try_from(combine_op.selected())
+										.ok()
+										.and_then(|i| SetOp::ALL.get(i))
+										.copied()
+										.unwrap_or(SetOp::Union);
+									sender.input(Message::Combine(
+										combine_left.selected(),
+										op,
+										combine_right.selected(),
+									));
+								}
+							},
 						},
 					},
-					gtk::Button {
-						set_css_classes: &["my-2", "py-2"],
-						set_label: "通用规范汉字表: 一级字表",
-						connect_clicked => {
-							Message::UpdateText(include_str!("common_cn_tier_1.txt").to_string())
+					adw::ExpanderRow {
+						set_css_classes: &["my-2"],
+						set_title: "Learn Next",
+						set_subtitle: "The most useful characters you don't know yet",
+						#[name = "batch_size"]
+						add_row = &adw::SpinRow::with_range(1.0, 500.0, 5.0) {
+							set_title: "Batch size",
+							set_value: 20.0,
+						},
+						#[name = "frequency_weight"]
+						add_row = &adw::SpinRow::with_range(0.0, 5.0, 0.25) {
+							set_title: "Frequency",
+							set_subtitle: "Common characters first",
+							set_digits: 2,
+							set_value: Weights::default().frequency,
+						},
+						#[name = "grade_weight"]
+						add_row = &adw::SpinRow::with_range(0.0, 5.0, 0.25) {
+							set_title: "Grade",
+							set_subtitle: "Characters taught early first",
+							set_digits: 2,
+							set_value: Weights::default().grade,
+						},
+						#[name = "components_weight"]
+						add_row = &adw::SpinRow::with_range(0.0, 5.0, 0.25) {
+							set_title: "Known components",
+							set_subtitle: "Characters built from ones you know first",
+							set_digits: 2,
+							set_value: Weights::default().components,
+						},
+						#[name = "phonetic_weight"]
+						add_row = &adw::SpinRow::with_range(0.0, 5.0, 0.25) {
+							set_title: "Known sound series",
+							set_subtitle: "Characters sounding like ones you know first",
+							set_digits: 2,
+							set_value: Weights::default().phonetic,
 						},
+						add_row = &gtk::Box {
+							set_css_classes: &["p-2"],
+							set_homogeneous: true,
+							set_spacing: 6,
+							gtk::Button {
+								set_label: "Save as List",
+								connect_clicked[sender, batch_size, frequency_weight, grade_weight, components_weight, phonetic_weight] => move |_| {
+									let (n, weights) = batch_settings(&batch_size, [&frequency_weight, &grade_weight, &components_weight, &phonetic_weight]);
+									sender.input(Message::Recommend(n, weights, BatchTarget::List));
+								}
+							},
+							gtk::Button {
+								set_css_classes: &["suggested-action"],
+								set_label: "Test",
+								connect_clicked[sender, batch_size, frequency_weight, grade_weight, components_weight, phonetic_weight] => move |_| {
+									let (n, weights) = batch_settings(&batch_size, [&frequency_weight, &grade_weight, &components_weight, &phonetic_weight]);
+									sender.input(Message::Recommend(n, weights, BatchTarget::Test));
+								}
+							},
+						},
+					},
+					adw::SwitchRow {
+						set_css_classes: &["my-2"],
+						set_title: "Keep list order",
+						set_subtitle: "Test in the order of the list instead of shuffling",
+						set_active: model.keep_order,
+						connect_active_notify[sender] => move |row| {
+							sender.input(Message::SetKeepOrder(row.is_active()));
+						}
+					},
+					adw::SwitchRow {
+						set_css_classes: &["my-2"],
+						set_title: "Collapse variants",
+						set_subtitle: "Test 学 and 學 as one character",
+						set_active: model.collapse_variants,
+						connect_active_notify[sender] => move |row| {
+							sender.input(Message::SetCollapseVariants(row.is_active()));
+						}
+					},
+					adw::SwitchRow {
+						set_css_classes: &["my-2"],
+						set_title: "Group by radical",
+						set_subtitle: "Test characters with the same radical together",
+						set_active: model.group_by_radical,
+						connect_active_notify[sender] => move |row| {
+							sender.input(Message::SetGroupByRadical(row.is_active()));
+						}
 					}
 				}
 			},
@@ -136,7 +460,8 @@ impl SimpleAsyncComponent for InputScreen {
 				match (model.test_exists, model.chinese_character_exists) {
 					(true, false) => gtk::Button {
 						set_css_classes: &["suggested-action", "pill", "mt-8", "mx-8"],
-						set_label: "Resume Test",
+						#[watch]
+						set_label: &resume_label(model.test_list.as_deref()),
 						set_hexpand: false,
 						connect_clicked[sender] => move |_| {
 							sender.output(OutputMessage::ResumeTest).expect("This shouldn't fail");
@@ -155,7 +480,8 @@ impl SimpleAsyncComponent for InputScreen {
 						},
 						gtk::Button {
 							set_css_classes: &["suggested-action", "pill", "mt-8", "mx-8"],
-							set_label: "Resume Test",
+							#[watch]
+							set_label: &resume_label(model.test_list.as_deref()),
 							set_hexpand: false,
 							connect_clicked[sender] => move |_| {
 								sender.output(OutputMessage::ResumeTest).expect("This shouldn't fail");
@@ -185,26 +511,82 @@ impl SimpleAsyncComponent for InputScreen {
 		widgets: Self::Root,
 		sender: AsyncComponentSender<Self>,
 	) -> AsyncComponentParts<Self> {
-		let db = init.0;
-		let data_dir = init.1;
+		let (db, dict, presets, variants, unihan, ids, radicals) = init;
+		let search = Arc::new(SearchIndex::new(&dict));
 		let text = db.get_text().await.expect("query failure");
 
-		let model = Self {
-			dict: bootstrap_dict(data_dir.to_path_buf()).expect("failed to bootstrap dictionary"),
+		let mut model = Self {
+			dict,
+			presets,
+			variants,
+			unihan,
+			ids,
+			search,
+			radicals,
 			db: db.clone(),
 			text,
 			test_exists: db.test_exists().await.expect("query failed"),
 			chinese_character_exists: db.chinese_character_exists().await.expect("query failed"),
+			keep_order: false,
+			collapse_variants: false,
+			group_by_radical: false,
+			import_by_occurrence: false,
+			lists: Vec::new(),
+			current_list: db.current_list().await.expect("query failed"),
+			test_list: db.test_list_name().await.expect("query failed"),
+			list_box: gtk::ListBox::new(),
+			source_names: gtk::StringList::new(&[]),
 		};
+		model.refresh_lists().await;
 
-		let dict = &model.dict;
+		let list_box = &model.list_box;
 
-		let jlpt: String = dict.get_all_jlpt().into_iter().collect();
-		let joyo: String = dict.get_all_joyo().into_iter().collect();
-		let kyoiku: String = dict.get_all_kyoiku().into_iter().collect();
+		#[allow(
+			clippy::as_conversions,
+			reason = "Small enough for an f64 to hold exactly"
+		)]
+		let max_freq_rank = MAX_FREQ_RANK as f64;
+		let op_names: Vec<String> = SetOp::ALL
+			.iter()
+			.map(|op| format!("{} {op}", op.symbol()))
+			.collect();
+		let op_names: Vec<&str> = op_names.iter().map(String::as_str).collect();
 
 		let widgets = view_output!();
 
+		for preset in model.presets.presets() {
+			widgets.preset_list.append(&preset_row(preset));
+		}
+		let presets = Arc::clone(&model.presets);
+		let search = widgets.preset_search.clone();
+		widgets.preset_list.set_filter_func(move |row| {
+			usize::try_from(row.index())
+				.ok()
+				.and_then(|index| presets.presets().get(index))
+				.is_none_or(|preset| preset.matches(&search.text()))
+		});
+		let drop_target = gtk::DropTarget::new(
+			gtk::gdk::FileList::static_type(),
+			gtk::gdk::DragAction::COPY,
+		);
+		let drop_sender = sender.clone();
+		drop_target.connect_drop(move |_, value, _, _| {
+			let Ok(files) = value.get::<gtk::gdk::FileList>() else {
+				return false;
+			};
+			let Some(path) = files.files().first().and_then(|file| file.path()) else {
+				return false;
+			};
+			drop_sender.input(Message::Import(path));
+			true
+		});
+		widgets.input_card.add_controller(drop_target);
+
+		let preset_list = widgets.preset_list.clone();
+		widgets
+			.preset_search
+			.connect_search_changed(move |_| preset_list.invalidate_filter());
+
 		AsyncComponentParts { model, widgets }
 	}
 
@@ -225,18 +607,165 @@ impl SimpleAsyncComponent for InputScreen {
 				);
 				self.text = s;
 			}
+			Message::Replace(text) => {
+				if self.current_list.is_some() {
+					self.select_list(None).await;
+				}
+				sender.input(Message::UpdateText(text));
+			}
+			Message::SelectList(row) => {
+				let id = usize::try_from(row)
+					.ok()
+					.and_then(|row| row.checked_sub(1))
+					.and_then(|index| self.lists.get(index))
+					.map(|list| list.id);
+				self.select_list(id).await;
+			}
+			Message::NewList => {
+				let name = format!("List {}", self.lists.len() + 1);
+				let id = self
+					.db
+					.create_list(&name, "", &self.text)
+					.await
+					.expect("insert failed");
+				self.select_list(Some(id)).await;
+			}
+			Message::DuplicateList => {
+				if let Some(id) = self.current_list {
+					let id = self.db.duplicate_list(id).await.expect("insert failed");
+					self.select_list(Some(id)).await;
+				}
+			}
+			Message::RenameList => {
+				if let Some(list) = self
+					.lists
+					.iter()
+					.find(|list| Some(list.id) == self.current_list)
+				{
+					self.rename_dialog(list, &sender);
+				}
+			}
+			Message::SetListDetails(id, name, description) => {
+				self.db
+					.rename_list(id, &name, &description)
+					.await
+					.expect("update failed");
+				self.refresh_lists().await;
+			}
+			Message::DeleteList => {
+				if let Some(id) = self.current_list {
+					self.db.delete_list(id).await.expect("delete failed");
+					self.select_list(None).await;
+				}
+			}
+			Message::UsePreset(index) => {
+				if let Some(preset) = self.presets.presets().get(index) {
+					sender.input(Message::Replace(preset.text()));
+				}
+			}
+			Message::UseMostFrequent(n) => {
+				let text: String = self.dict.get_most_frequent(n).into_iter().collect();
+				sender.input(Message::Replace(text));
+			}
+			Message::SetKeepOrder(keep_order) => {
+				self.keep_order = keep_order;
+			}
+			Message::SetCollapseVariants(collapse) => {
+				self.collapse_variants = collapse;
+			}
+			Message::SetGroupByRadical(group) => {
+				self.group_by_radical = group;
+			}
+			Message::Import(path) => match read_text(&path) {
+				Ok(text) => {
+					let mut counts = count_characters(&text);
+					if self.import_by_occurrence {
+						counts.sort_by_key(|(_, n)| Reverse(*n));
+					}
+					let chars: String = counts.into_iter().map(|(c, _)| c).collect();
+					sender.input(Message::Replace(chars));
+				}
+				Err(e) => {
+					warn!("Failed to import {}: {e}", path.display());
+					show_import_error(&self.list_box, &e);
+				}
+			},
+			Message::SetImportByOccurrence(by_occurrence) => {
+				self.import_by_occurrence = by_occurrence;
+			}
+			Message::Combine(left, op, right) => {
+				let left = self.list(self.list_source(left)).await;
+				let right = self.list(self.list_source(right)).await;
+				let text: String = op.apply(&left, &right).into_iter().collect();
+				sender.input(Message::Replace(text));
+			}
+			Message::Recommend(n, weights, target) => {
+				let text = self.recommend(n, weights).await;
+				match target {
+					BatchTarget::List => {
+						let name = format!("Learn Next {}", self.lists.len() + 1);
+						let id = self
+							.db
+							.create_list(&name, "Recommended from what you know", &text)
+							.await
+							.expect("insert failed");
+						self.select_list(Some(id)).await;
+					}
+					BatchTarget::Test => {
+						if self.current_list.is_some() {
+							self.select_list(None).await;
+						}
+						sender.input(Message::UpdateText(text));
+						sender.input(Message::NewTest);
+					}
+				}
+			}
+			Message::BrowseComponents => {
+				sender.output_sender().emit(OutputMessage::BrowseComponents(
+					extract(&self.text).into_chars(),
+				));
+			}
+			Message::Search => {
+				let status = search::Status {
+					known: self.list(ListSource::Known).await.into_iter().collect(),
+					unknown: self.list(ListSource::Unknown).await.into_iter().collect(),
+					in_list: self.list(ListSource::Current).await.into_iter().collect(),
+				};
+				let add_sender = sender.clone();
+				search::present(
+					&self.list_box,
+					Arc::clone(&self.search),
+					Arc::clone(&self.dict),
+					status,
+					move |c| add_sender.input(Message::AddCharacter(c)),
+				);
+			}
+			Message::AddCharacter(c) => {
+				if !extract(&self.text).chars().contains(&c) {
+					self.text.push(c);
+					self.db.set_text(&self.text).await.expect("insert failed");
+					self.chinese_character_exists = true;
+				}
+			}
 			Message::NewTest => {
-				// save new test to disk
+				// Save the new test to disk, then send the testing screen the order that was saved.
 				self.db.set_text(&self.text).await.expect("insert failed");
 				self.db.set_test_progress(0).await.expect("failed");
-				self.db.create_test_from_raw_text().await.expect("failed");
+				self.db
+					.create_test_from_raw_text(
+						!self.keep_order,
+						self.test_variants(),
+						self.test_radicals(),
+					)
+					.await
+					.expect("failed");
 				let test = self
 					.db
 					.get_previous_test()
 					.await
 					.expect("failed")
 					.expect("should exist");
-				self.db.create_test_from_raw_text().await.expect("failed");
+				self.test_list = self.db.test_list_name().await.expect("query failed");
 
 				sender.output_sender().emit(OutputMessage::NewTest(test));
 			}
@@ -244,6 +773,280 @@ impl SimpleAsyncComponent for InputScreen {
 	}
 }
 
+impl InputScreen {
+	fn test_variants(&self) -> Option<&Variants> {
+		self.collapse_variants.then_some(&*self.variants)
+	}
+
+	fn test_radicals(&self) -> Option<&RadicalIndex> {
+		self.group_by_radical.then_some(&*self.radicals)
+	}
+
+	/// The best `n` characters to learn next. The ones unknown in the last test come first, and
+	/// the rest of KANJIDIC2 fills up the batch.
+	async fn recommend(&self, n: usize, weights: Weights) -> String {
+		let known: HashSet<char> = self.list(ListSource::Known).await.into_iter().collect();
+		let unknown = self.list(ListSource::Unknown).await;
+		let recommender = Recommender::new(&self.dict)
+			.with_unihan(&self.unihan)
+			.with_ids(&self.ids)
+			.with_weights(weights);
+		let mut batch = recommender.recommend(unknown.iter().copied(), &known, n);
+		if batch.len() < n {
+			let seen: HashSet<char> = known.iter().chain(&unknown).copied().collect();
+			let rest = self
+				.dict
+				.characters()
+				.iter()
+				.filter_map(|c| c.literal().chars().next());
+			batch.extend(recommender.recommend(rest, &seen, n - batch.len()));
+		}
+		batch.iter().map(Recommendation::char).collect()
+	}
+
+	async fn list(&self, source: ListSource) -> Vec<char> {
+		match source {
+			ListSource::Current => extract(&self.text).into_chars(),
+			ListSource::Known => self
+				.db
+				.get_recalled_characters(Recalled::Known)
+				.await
+				.expect("query failed"),
+			ListSource::Unknown => self
+				.db
+				.get_recalled_characters(Recalled::Unknown)
+				.await
+				.expect("query failed"),
+			ListSource::Saved(id) => {
+				extract(&self.db.get_list_text(id).await.expect("query failed")).into_chars()
+			}
+			ListSource::Preset(index) => self
+				.presets
+				.presets()
+				.get(index)
+				.map(|preset| preset.characters().to_vec())
+				.unwrap_or_default(),
+		}
+	}
+
+	/// Every list that can be combined, in the order the combine drop-downs show them.
+	fn list_sources(&self) -> impl Iterator<Item = ListSource> + '_ {
+		ListSource::FIXED
+			.into_iter()
+			.chain(self.lists.iter().map(|list| ListSource::Saved(list.id)))
+			.chain((0..self.presets.presets().len()).map(ListSource::Preset))
+	}
+
+	fn list_source(&self, index: u32) -> ListSource {
+		let index = usize::try_from(index).expect("u32 should fit in usize");
+		self.list_sources()
+			.nth(index)
+			.unwrap_or(ListSource::Current)
+	}
+
+	fn source_name(&self, source: ListSource) -> String {
+		match source {
+			ListSource::Current => "Current list".to_owned(),
+			ListSource::Known => "Known in the last test".to_owned(),
+			ListSource::Unknown => "Unknown in the last test".to_owned(),
+			ListSource::Saved(id) => self
+				.lists
+				.iter()
+				.find(|list| list.id == id)
+				.map(|list| list.name.clone())
+				.unwrap_or_default(),
+			ListSource::Preset(index) => self.presets.presets()[index].name().to_owned(),
+		}
+	}
+
+	async fn select_list(&mut self, id: Option<i64>) {
+		self.db.set_current_list(id).await.expect("update failed");
+		self.current_list = id;
+		self.text = self.db.get_text().await.expect("query failed");
+		self.chinese_character_exists = self
+			.db
+			.chinese_character_exists()
+			.await
+			.expect("query failed");
+		self.refresh_lists().await;
+	}
+
+	/// Reloads the saved lists into the sidebar and the combine drop-downs.
+	async fn refresh_lists(&mut self) {
+		self.lists = self.db.saved_lists().await.expect("query failed");
+
+		while let Some(row) = self.list_box.first_child() {
+			self.list_box.remove(&row);
+		}
+		let unsaved = adw::ActionRow::builder()
+			.title("Unsaved list")
+			.subtitle("Presets and imports go here")
+			.activatable(true)
+			.build();
+		self.list_box.append(&unsaved);
+		for list in &self.lists {
+			let created = list.created_at.split(' ').next().unwrap_or_default();
+			let subtitle = if list.description.is_empty() {
+				format!("Created {created}")
+			} else {
+				format!("{}\nCreated {created}", list.description)
+			};
+			let row = adw::ActionRow::builder()
+				.title(&list.name)
+				.subtitle(subtitle)
+				.activatable(true)
+				.build();
+			self.list_box.append(&row);
+		}
+		let selected = self
+			.current_list
+			.and_then(|id| self.lists.iter().position(|list| list.id == id))
+			.map_or(0, |index| index + 1);
+		let selected = i32::try_from(selected).expect("there shouldn't be that many lists");
+		self.list_box
+			.select_row(self.list_box.row_at_index(selected).as_ref());
+
+		let names: Vec<String> = self
+			.list_sources()
+			.map(|source| self.source_name(source))
+			.collect();
+		let names: Vec<&str> = names.iter().map(String::as_str).collect();
+		self.source_names
+			.splice(0, self.source_names.n_items(), &names);
+	}
+
+	fn rename_dialog(&self, list: &SavedList, sender: &AsyncComponentSender<Self>) {
+		let name = gtk::Entry::builder().text(&list.name).build();
+		let description = gtk::Entry::builder()
+			.text(&list.description)
+			.placeholder_text("Description")
+			.build();
+		let fields = gtk::Box::new(gtk::Orientation::Vertical, 6);
+		fields.append(&name);
+		fields.append(&description);
+
+		let dialog = adw::AlertDialog::new(Some("Rename List"), None);
+		dialog.add_responses(&[("cancel", "Cancel"), ("rename", "Rename")]);
+		dialog.set_response_appearance("rename", adw::ResponseAppearance::Suggested);
+		dialog.set_default_response(Some("rename"));
+		dialog.set_extra_child(Some(&fields));
+		let id = list.id;
+		let sender = sender.clone();
+		dialog.connect_response(Some("rename"), move |_, _| {
+			sender.input(Message::SetListDetails(
+				id,
+				name.text().to_string(),
+				description.text().to_string(),
+			));
+		});
+		dialog.present(Some(&self.list_box));
+	}
+}
+
+/// Tells the user why a file couldn't be imported.
+pub(crate) fn show_import_error(parent: &impl IsA<gtk::Widget>, error: &import::Error) {
+	let mut reason = error.to_string();
+	let mut source = error.source();
+	while let Some(e) = source {
+		reason.push_str(": ");
+		reason.push_str(&e.to_string());
+		source = e.source();
+	}
+	let dialog = adw::AlertDialog::new(Some("Import Failed"), Some(&reason));
+	dialog.add_response("close", "Close");
+	dialog.present(Some(parent));
+}
+
+/// A list that can be combined with another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListSource {
+	Current,
+	/// Answered as known in the last test.
+	Known,
+	/// Answered as not known. Characters the test didn't reach are in neither list.
+	Unknown,
+	/// By id.
+	Saved(i64),
+	/// By index into the [`PresetRegistry`].
+	Preset(usize),
+}
+
+impl ListSource {
+	const FIXED: [Self; 3] = [Self::Current, Self::Known, Self::Unknown];
+}
+
+fn resume_label(test_list: Option<&str>) -> String {
+	test_list.map_or_else(
+		|| "Resume Test".to_owned(),
+		|name| format!("Resume Test: {name}"),
+	)
+}
+
+fn preset_row(preset: &Preset) -> adw::ActionRow {
+	let details = format!(
+		"{} · {} Characters",
+		preset.language().name(),
+		preset.characters().len()
+	);
+	let subtitle = if preset.description().is_empty() {
+		details
+	} else {
+		format!("{}\n{details}", preset.description())
+	};
+	adw::ActionRow::builder()
+		.title(preset.name())
+		.subtitle(subtitle)
+		.activatable(true)
+		.build()
+}
+
+/// The number of characters a test would have, and what was left out of it.
+fn counter_label(extraction: &Extraction) -> String {
+	let mut notes = Vec::new();
+	match extraction.duplicate_count() {
+		0 => {}
+		1 => notes.push("1 duplicate removed".to_owned()),
+		n => notes.push(format!("{n} duplicates removed")),
+	}
+	match extraction.normalized().len() {
+		0 => {}
+		1 => notes.push("1 compatibility ideograph unified".to_owned()),
+		n => notes.push(format!("{n} compatibility ideographs unified")),
+	}
+	match extraction.variation_selectors() {
+		0 => {}
+		1 => notes.push("1 variation selector stripped".to_owned()),
+		n => notes.push(format!("{n} variation selectors stripped")),
+	}
+	let count = extraction.chars().len();
+	if notes.is_empty() {
+		format!("{count} Characters")
+	} else {
+		format!("{count} Characters ({})", notes.join(", "))
+	}
+}
+
+/// The batch size and weights set in the Learn Next rows.
+fn batch_settings(batch_size: &adw::SpinRow, weights: [&adw::SpinRow; 4]) -> (usize, Weights) {
+	#[allow(
+		clippy::cast_possible_truncation,
+		clippy::cast_sign_loss,
+		clippy::as_conversions,
+		reason = "The spin row only holds whole numbers from 1 to 500"
+	)]
+	let n = batch_size.value() as usize;
+	let [frequency, grade, components, phonetic] = weights.map(|row| row.value());
+	(
+		n,
+		Weights {
+			frequency,
+			grade,
+			components,
+			phonetic,
+		},
+	)
+}
+
 fn get_full_text_from_buffer(buf: &gtk::TextBuffer) -> String {
 	let (start, end) = buf.bounds();
 	buf.text(&start, &end, true).to_string()
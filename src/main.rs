@@ -9,9 +9,14 @@ use relm4::{adw, gtk, prelude::*};
 use tracing::{error, info};
 use tracing::warn;
 
+mod components;
+mod coverage;
 mod db;
+mod detail;
 mod input;
+mod radicals;
 mod result;
+mod search;
 mod testing;
 mod window;
 
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use lib::coverage::ratio;
+use lib::radical::{RadicalGroup, RadicalIndex};
+use relm4::adw::prelude::*;
+use relm4::component;
+use relm4::gtk;
+use relm4::prelude::*;
+
+#[derive(Debug)]
+pub struct RadicalsScreen {
+	radicals: Arc<RadicalIndex>,
+	known: HashSet<char>,
+	/// Index into the groups of [`RadicalIndex`].
+	selected: Option<usize>,
+	radical_list: gtk::ListBox,
+	character_list: gtk::ListBox,
+}
+
+#[component(pub, async)]
+impl SimpleAsyncComponent for RadicalsScreen {
+	type Init = Arc<RadicalIndex>;
+	type Input = Message;
+	type Output = OutputMessage;
+
+	view! {
+		#[root]
+		gtk::Box {
+			set_orientation: gtk::Orientation::Vertical,
+			set_hexpand: true,
+			gtk::Label {
+				set_css_classes: &["heading"],
+				set_label: "Radicals",
+			},
+			gtk::Label {
+				set_css_classes: &["mb-2"],
+				set_label: "How many of the characters filed under each radical you know.",
+			},
+			gtk::Box {
+				set_orientation: gtk::Orientation::Horizontal,
+				set_homogeneous: true,
+				set_spacing: 8,
+				gtk::ScrolledWindow {
+					set_vexpand: true,
+					set_min_content_height: 240,
+					set_hscrollbar_policy: gtk::PolicyType::Never,
+					#[local_ref]
+					radical_list -> gtk::ListBox {
+						set_css_classes: &["boxed-list"],
+						connect_row_activated[sender] => move |_, row| {
+							sender.input(Message::Select(row.index()));
+						},
+					},
+				},
+				gtk::Box {
+					set_orientation: gtk::Orientation::Vertical,
+					gtk::Label {
+						set_css_classes: &["heading", "mb-2"],
+						#[watch]
+						set_label: &model
+							.selected_group()
+							.map_or_else(|| "Pick a radical".to_owned(), radical_title),
+					},
+					gtk::ScrolledWindow {
+						set_vexpand: true,
+						set_hscrollbar_policy: gtk::PolicyType::Never,
+						#[local_ref]
+						character_list -> gtk::ListBox {
+							set_css_classes: &["boxed-list"],
+							set_selection_mode: gtk::SelectionMode::None,
+						},
+					},
+				},
+			},
+			gtk::Box {
+				set_css_classes: &["mt-8"],
+				set_homogeneous: true,
+				gtk::Button {
+					set_css_classes: &["suggested-action", "pill", "mx-2"],
+					set_label: "Back",
+					connect_clicked[sender] => move |_| {
+						sender.output(OutputMessage::Back).expect("Shouldn't fail");
+					}
+				},
+			},
+		}
+	}
+
+	async fn init(
+		radicals: Self::Init,
+		widgets: Self::Root,
+		sender: AsyncComponentSender<Self>,
+	) -> AsyncComponentParts<Self> {
+		let model = Self {
+			radicals,
+			known: HashSet::new(),
+			selected: None,
+			radical_list: gtk::ListBox::new(),
+			character_list: gtk::ListBox::new(),
+		};
+
+		let radical_list = &model.radical_list;
+		let character_list = &model.character_list;
+
+		let widgets = view_output!();
+
+		AsyncComponentParts { model, widgets }
+	}
+
+	async fn update(&mut self, message: Self::Input, _sender: AsyncComponentSender<Self>) {
+		match message {
+			Message::SetKnown(known) => {
+				self.known = known;
+				self.list_radicals();
+				self.list_characters();
+			}
+			Message::Select(row) => {
+				self.selected = usize::try_from(row).ok();
+				self.list_characters();
+			}
+		}
+	}
+}
+
+impl RadicalsScreen {
+	fn selected_group(&self) -> Option<&RadicalGroup> {
+		self.selected
+			.and_then(|index| self.radicals.groups().get(index))
+	}
+
+	fn list_radicals(&self) {
+		clear(&self.radical_list);
+		for group in self.radicals.groups() {
+			let known = group.known(&self.known);
+			let total = group.characters().len();
+			let row = adw::ActionRow::builder()
+				.title(radical_title(group))
+				.subtitle(format!("{known} of {total} known"))
+				.activatable(true)
+				.build();
+			let level = gtk::LevelBar::builder()
+				.valign(gtk::Align::Center)
+				.width_request(80)
+				.value(ratio(known, total))
+				.build();
+			row.add_suffix(&level);
+			self.radical_list.append(&row);
+		}
+		if let Some(index) = self.selected.and_then(|index| i32::try_from(index).ok()) {
+			self.radical_list
+				.select_row(self.radical_list.row_at_index(index).as_ref());
+		}
+	}
+
+	fn list_characters(&self) {
+		clear(&self.character_list);
+		let Some(group) = self.selected_group() else {
+			return;
+		};
+		for c in group.characters() {
+			let row = adw::ActionRow::builder()
+				.title(c.to_string())
+				.subtitle(if self.known.contains(c) {
+					"Known"
+				} else {
+					"Not known yet"
+				})
+				.build();
+			self.character_list.append(&row);
+		}
+	}
+}
+
+/// Like `一 1 · いち`.
+fn radical_title(group: &RadicalGroup) -> String {
+	let mut title = group.radical().map_or_else(
+		|| format!("Radical {}", group.number()),
+		|radical| format!("{radical} {}", group.number()),
+	);
+	if !group.names().is_empty() {
+		title.push_str(" · ");
+		title.push_str(&group.names().join(", "));
+	}
+	title
+}
+
+fn clear(list: &gtk::ListBox) {
+	while let Some(row) = list.first_child() {
+		list.remove(&row);
+	}
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+	/// The characters to count as known, from the profile.
+	SetKnown(HashSet<char>),
+	/// By row in the radical list.
+	Select(i32),
+}
+
+#[derive(Debug, Clone)]
+pub enum OutputMessage {
+	Back,
+}
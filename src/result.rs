@@ -1,18 +1,51 @@
+use std::collections::HashSet;
 use std::rc::Rc;
+use std::sync::Arc;
 
+use lib::cedict::Cedict;
+use lib::extract::extract;
+use lib::jmdict::Jmdict;
+use lib::load_kanjidic::Dict2;
+use lib::lookup::Lookup;
+use lib::preset::PresetRegistry;
+use lib::unihan::Unihan;
+use lib::variant::Variants;
+use lib::vocabulary::Vocabulary;
 use relm4::adw::prelude::*;
 use relm4::component;
 use relm4::gtk;
 use relm4::prelude::*;
 
+use crate::detail::char_at;
+use crate::testing::{readings_text, variants_text};
+
 #[derive(Debug)]
 pub struct ResultScreen {
 	buf: gtk::TextBuffer,
+	presets: Arc<PresetRegistry>,
+	/// How much of each graded series the tested characters cover.
+	levels: gtk::ListBox,
+	dict: Arc<Dict2>,
+	unihan: Arc<Unihan>,
+	variants: Arc<Variants>,
+	cedict: Arc<Cedict>,
+	jmdict: Arc<Jmdict>,
+	/// The readings, variants of and example words with the tested characters.
+	char_details: gtk::ListBox,
+	/// What the known characters make readable in each word dictionary.
+	vocabulary: gtk::ListBox,
 }
 
 #[component(pub, async)]
 impl SimpleAsyncComponent for ResultScreen {
-	type Init = ();
+	type Init = (
+		Arc<PresetRegistry>,
+		Arc<Dict2>,
+		Arc<Unihan>,
+		Arc<Variants>,
+		Arc<Cedict>,
+		Arc<Jmdict>,
+	);
 	type Input = Message;
 	type Output = OutputMessage;
 
@@ -41,9 +74,36 @@ impl SimpleAsyncComponent for ResultScreen {
 						set_wrap_mode: gtk::WrapMode::WordChar,
 						set_editable: false,
 						set_buffer: Some(&model.buf),
+						set_tooltip_text: Some("Click a character to see its details"),
+						add_controller = gtk::GestureClick {
+							connect_released[sender] => move |gesture, _, x, y| {
+								let view = gesture.widget().and_downcast::<gtk::TextView>();
+								if let Some(c) = view.and_then(|view| char_at(&view, x, y)) {
+									sender.output(OutputMessage::ShowCharacter(c)).expect("Shouldn't fail");
+								}
+							},
+						},
 					},
 				}
 			},
+			#[local_ref]
+			levels -> gtk::ListBox {
+				set_css_classes: &["boxed-list", "mt-4"],
+				set_selection_mode: gtk::SelectionMode::None,
+				set_visible: false,
+			},
+			#[local_ref]
+			char_details -> gtk::ListBox {
+				set_css_classes: &["boxed-list", "mt-4"],
+				set_selection_mode: gtk::SelectionMode::None,
+				set_visible: false,
+			},
+			#[local_ref]
+			vocabulary -> gtk::ListBox {
+				set_css_classes: &["boxed-list", "mt-4"],
+				set_selection_mode: gtk::SelectionMode::None,
+				set_visible: false,
+			},
 			gtk::Box {
 				set_css_classes: &["mt-8"],
 				set_homogeneous: true,
@@ -56,6 +116,24 @@ impl SimpleAsyncComponent for ResultScreen {
 						sender.output(OutputMessage::StartOver).expect("Shouldn't fail");
 					}
 				},
+				gtk::Button {
+					set_css_classes: &["pill", "mx-2"],
+					set_tooltip: "See how much of a text you can read",
+					set_label: "Check a Text",
+					set_hexpand: false,
+					connect_clicked[sender] => move |_| {
+						sender.output(OutputMessage::CheckText).expect("Shouldn't fail");
+					}
+				},
+				gtk::Button {
+					set_css_classes: &["pill", "mx-2"],
+					set_tooltip: "See how much of each radical you know",
+					set_label: "Radicals",
+					set_hexpand: false,
+					connect_clicked[sender] => move |_| {
+						sender.output(OutputMessage::Radicals).expect("Shouldn't fail");
+					}
+				},
 				gtk::Button {
 					set_css_classes: &["suggested-action", "pill", "mx-2"],
 					set_label: "Exit",
@@ -69,13 +147,28 @@ impl SimpleAsyncComponent for ResultScreen {
 	}
 
 	async fn init(
-		_init: Self::Init,
+		(presets, dict, unihan, variants, cedict, jmdict): Self::Init,
 		widgets: Self::Root,
 		sender: AsyncComponentSender<Self>,
 	) -> AsyncComponentParts<Self> {
 		let tag_table = Rc::new(gtk::TextTagTable::new());
 		let buf = gtk::TextBuffer::new(Some(&tag_table));
-		let model = Self { buf };
+		let model = Self {
+			buf,
+			presets,
+			levels: gtk::ListBox::new(),
+			dict,
+			unihan,
+			variants,
+			cedict,
+			jmdict,
+			char_details: gtk::ListBox::new(),
+			vocabulary: gtk::ListBox::new(),
+		};
+
+		let levels = &model.levels;
+		let char_details = &model.char_details;
+		let vocabulary = &model.vocabulary;
 
 		let widgets = view_output!();
 
@@ -90,7 +183,7 @@ impl SimpleAsyncComponent for ResultScreen {
 					.buf
 					.create_tag(None, &[("foreground", &"blue")])
 					.expect("fails if property doesn't exist or is not writeable");
-				for cjk_c in known_chars {
+				for cjk_c in &known_chars {
 					let mut txt_iter = self.buf.start_iter();
 					let end_iter = self.buf.end_iter();
 					while let Some((match_start, match_end)) = txt_iter.forward_search(
@@ -102,9 +195,231 @@ impl SimpleAsyncComponent for ResultScreen {
 						txt_iter = match_end;
 					}
 				}
+				let known = known_chars.into_iter().collect();
+				self.show_levels(&initial_input, &known);
+				self.show_char_details(&initial_input);
+				self.show_vocabulary(&known);
+			}
+		}
+	}
+}
+
+impl ResultScreen {
+	/// Lists every level of the series the tested characters come from.
+	fn show_levels(&self, tested: &str, known: &HashSet<char>) {
+		while let Some(row) = self.levels.first_child() {
+			self.levels.remove(&row);
+		}
+		let tested: HashSet<char> = tested.chars().collect();
+		for series in self.presets.series() {
+			let breakdown = self.presets.breakdown(series, known);
+			let overlaps = breakdown.iter().any(|level| {
+				level
+					.preset()
+					.characters()
+					.iter()
+					.any(|c| tested.contains(c))
+			});
+			if !overlaps {
+				continue;
 			}
+			for level in breakdown {
+				let row = adw::ActionRow::builder()
+					.title(level.preset().name())
+					.subtitle(format!(
+						"You know {} of {} characters",
+						level.known(),
+						level.total()
+					))
+					.build();
+				self.levels.append(&row);
+			}
+		}
+		self.levels.set_visible(self.levels.first_child().is_some());
+	}
+
+	/// Lists the readings and meanings of the tested characters, their variants, which are
+	/// usually easy to recognize once the character itself is known, and common words they're
+	/// used in.
+	fn show_char_details(&self, tested: &str) {
+		while let Some(row) = self.char_details.first_child() {
+			self.char_details.remove(&row);
+		}
+		let tested = extract(tested).into_chars();
+		let readings = self.readings_row(&tested);
+		let variants = self.variants_row(&tested);
+		let words = self.example_words_row(&tested);
+		let rows = [readings, variants, words];
+		for row in rows.iter().flatten() {
+			self.char_details.append(row);
+		}
+		self.char_details
+			.set_visible(rows.iter().any(Option::is_some));
+	}
+
+	/// Reports on the words of each loaded dictionary that can be read with the known
+	/// characters.
+	fn show_vocabulary(&self, known: &HashSet<char>) {
+		while let Some(row) = self.vocabulary.first_child() {
+			self.vocabulary.remove(&row);
+		}
+		let rows = [
+			vocabulary_row(
+				"Chinese words you can read",
+				&Vocabulary::analyze(self.cedict.weighted_words(), known),
+			),
+			vocabulary_row(
+				"Japanese words you can read",
+				&Vocabulary::analyze(self.jmdict.weighted_words(), known),
+			),
+		];
+		for row in rows.iter().flatten() {
+			self.vocabulary.append(row);
+		}
+		self.vocabulary
+			.set_visible(rows.iter().any(Option::is_some));
+	}
+
+	fn readings_row(&self, tested: &[char]) -> Option<adw::ExpanderRow> {
+		let expander = adw::ExpanderRow::builder()
+			.title("Readings and meanings")
+			.subtitle("From KANJIDIC2 and Unihan")
+			.build();
+		let lookup = Lookup::new(&self.dict).with_unihan(&self.unihan);
+		let mut any = false;
+		for &c in tested {
+			let text = readings_text(&lookup.get(c));
+			if text.is_empty() {
+				continue;
+			}
+			let row = adw::ActionRow::builder()
+				.title(c.to_string())
+				.subtitle(text)
+				.build();
+			expander.add_row(&row);
+			any = true;
+		}
+		any.then_some(expander)
+	}
+
+	fn variants_row(&self, tested: &[char]) -> Option<adw::ExpanderRow> {
+		let expander = adw::ExpanderRow::builder().title("Variants").build();
+		let mut count = 0;
+		for &c in tested {
+			let text = variants_text(&self.variants, c);
+			if text.is_empty() {
+				continue;
+			}
+			let row = adw::ActionRow::builder()
+				.title(c.to_string())
+				.subtitle(text)
+				.build();
+			expander.add_row(&row);
+			count += 1;
+		}
+		expander.set_subtitle(&format!("{count} of the tested characters have variants"));
+		(count > 0).then_some(expander)
+	}
+
+	fn example_words_row(&self, tested: &[char]) -> Option<adw::ExpanderRow> {
+		let expander = adw::ExpanderRow::builder()
+			.title("Example words")
+			.subtitle("Common words with the tested characters")
+			.build();
+		let mut any = false;
+		for &c in tested {
+			let chinese = self.cedict.words_with(c, 3).into_iter().map(|word| {
+				format!(
+					"{} ({}) {}",
+					word.written_with(c),
+					word.pinyin(),
+					word.glosses().join("; ")
+				)
+			});
+			let japanese = self
+				.jmdict
+				.words_with(c, 3)
+				.into_iter()
+				.filter_map(|entry| Some((entry.written_with(c)?, entry)))
+				.map(|(form, entry)| {
+					format!(
+						"{} ({}) {}",
+						form.text(),
+						entry.readings().join(", "),
+						entry.glosses().join("; ")
+					)
+				});
+			let lines: Vec<String> = chinese.chain(japanese).collect();
+			if lines.is_empty() {
+				continue;
+			}
+			let subtitle = lines.join("\n");
+			let row = adw::ActionRow::builder()
+				.title(c.to_string())
+				.subtitle(subtitle)
+				.build();
+			expander.add_row(&row);
+			any = true;
+		}
+		any.then_some(expander)
+	}
+}
+
+/// How many words of a dictionary the known characters make readable, and the words and
+/// characters that would add the most.
+fn vocabulary_row(title: &str, vocabulary: &Vocabulary<'_>) -> Option<adw::ExpanderRow> {
+	if vocabulary.words() == 0 {
+		return None;
+	}
+	let expander = adw::ExpanderRow::builder()
+		.title(title)
+		.subtitle(format!(
+			"{} of {} words, {:.1}% weighted by frequency",
+			vocabulary.readable_words(),
+			vocabulary.words(),
+			vocabulary.weighted_coverage() * 100.0
+		))
+		.build();
+	let lists = [
+		(
+			"Most common words you can read",
+			vocabulary
+				.readable()
+				.iter()
+				.take(20)
+				.map(|(form, _)| (*form).to_owned())
+				.collect::<Vec<_>>(),
+		),
+		(
+			"Learn next to read the most new words",
+			vocabulary
+				.unlocks()
+				.iter()
+				.take(10)
+				.map(|(c, _)| c.to_string())
+				.collect(),
+		),
+		(
+			"Words one character away",
+			vocabulary
+				.one_away()
+				.iter()
+				.take(20)
+				.map(|word| format!("{} ({})", word.form(), word.missing()))
+				.collect(),
+		),
+	];
+	for (title, items) in lists {
+		if items.is_empty() {
+			continue;
 		}
+		let row = adw::ActionRow::builder()
+			.title(title)
+			.subtitle(items.join(", "))
+			.build();
+		expander.add_row(&row);
 	}
+	Some(expander)
 }
 
 #[derive(Debug, Clone)]
@@ -115,5 +430,8 @@ pub enum Message {
 #[derive(Debug, Clone)]
 pub enum OutputMessage {
 	StartOver,
+	CheckText,
+	Radicals,
+	ShowCharacter(char),
 	Exit,
 }
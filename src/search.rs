@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use lib::load_kanjidic::Dict2;
+use lib::search::SearchIndex;
+use relm4::adw::prelude::*;
+use relm4::{adw, gtk};
+
+/// How many results to show at most.
+const MAX_RESULTS: usize = 200;
+
+/// What the profile knows about characters, to label the search results with.
+#[derive(Debug, Clone, Default)]
+pub struct Status {
+	/// Answered as known in the last test.
+	pub known: HashSet<char>,
+	pub unknown: HashSet<char>,
+	/// Already in the current list.
+	pub in_list: HashSet<char>,
+}
+
+impl Status {
+	fn label(&self, c: char) -> &'static str {
+		if self.known.contains(&c) {
+			"Known"
+		} else if self.unknown.contains(&c) {
+			"Not known yet"
+		} else {
+			"Not tested yet"
+		}
+	}
+}
+
+/// Shows a dialog to look characters up in KANJIDIC2. `add` is called for every character the
+/// user adds to the current list.
+pub fn present(
+	parent: &impl IsA<gtk::Widget>,
+	index: Arc<SearchIndex>,
+	dict: Arc<Dict2>,
+	status: Status,
+	add: impl Fn(char) + 'static,
+) {
+	let entry = gtk::SearchEntry::builder()
+		.placeholder_text("Reading, meaning, strokes, r61, 1-4-3 or 4060")
+		.build();
+	let hint = gtk::Label::builder()
+		.css_classes(["dim-label", "caption"])
+		.label("Readings can be typed in kana or romaji. Separate terms with spaces.")
+		.wrap(true)
+		.build();
+	let results = gtk::ListBox::builder()
+		.css_classes(["boxed-list"])
+		.selection_mode(gtk::SelectionMode::None)
+		.valign(gtk::Align::Start)
+		.build();
+	let scrolled = gtk::ScrolledWindow::builder()
+		.vexpand(true)
+		.hscrollbar_policy(gtk::PolicyType::Never)
+		.child(&results)
+		.build();
+	let content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+	content.set_css_classes(&["p-4"]);
+	content.append(&entry);
+	content.append(&hint);
+	content.append(&scrolled);
+
+	let toolbar = adw::ToolbarView::new();
+	toolbar.add_top_bar(&adw::HeaderBar::new());
+	toolbar.set_content(Some(&content));
+	let dialog = adw::Dialog::builder()
+		.title("Search Characters")
+		.content_width(420)
+		.content_height(560)
+		.child(&toolbar)
+		.build();
+
+	let status = Rc::new(RefCell::new(status));
+	let add = Rc::new(add);
+	entry.connect_search_changed(move |entry| {
+		while let Some(row) = results.first_child() {
+			results.remove(&row);
+		}
+		for c in index.search(&entry.text()).into_iter().take(MAX_RESULTS) {
+			results.append(&result_row(c, &dict, &status, &add));
+		}
+	});
+	dialog.present(Some(parent));
+}
+
+fn result_row(
+	c: char,
+	dict: &Dict2,
+	status: &Rc<RefCell<Status>>,
+	add: &Rc<impl Fn(char) + 'static>,
+) -> adw::ActionRow {
+	let meanings: Vec<&str> = dict
+		.get(c)
+		.into_iter()
+		.flat_map(|character| character.meanings())
+		.filter(|meaning| meaning.lang() == "en")
+		.map(|meaning| meaning.value())
+		.take(3)
+		.collect();
+	let label = status.borrow().label(c);
+	let subtitle = if meanings.is_empty() {
+		label.to_owned()
+	} else {
+		format!("{label} · {}", meanings.join(", "))
+	};
+	let row = adw::ActionRow::builder()
+		.title(c.to_string())
+		.subtitle(subtitle)
+		.build();
+
+	let in_list = status.borrow().in_list.contains(&c);
+	let button = gtk::Button::builder()
+		.valign(gtk::Align::Center)
+		.label(if in_list { "In the List" } else { "Add" })
+		.sensitive(!in_list)
+		.build();
+	let status = Rc::clone(status);
+	let add = Rc::clone(add);
+	button.connect_clicked(move |button| {
+		add(c);
+		status.borrow_mut().in_list.insert(c);
+		button.set_label("In the List");
+		button.set_sensitive(false);
+	});
+	row.add_suffix(&button);
+	row
+}
@@ -1,3 +1,11 @@
+use std::sync::Arc;
+
+use lib::cedict::Cedict;
+use lib::jmdict::Jmdict;
+use lib::load_kanjidic::Dict2;
+use lib::lookup::{CharInfo, Lookup};
+use lib::unihan::Unihan;
+use lib::variant::Variants;
 use relm4::adw::prelude::*;
 use relm4::component;
 use relm4::gtk;
@@ -16,6 +24,11 @@ pub struct TestingScreen {
 	tests: Vec<Test>,
 	current_test: usize,
 	db: Db,
+	dict: Arc<Dict2>,
+	unihan: Arc<Unihan>,
+	variants: Arc<Variants>,
+	cedict: Arc<Cedict>,
+	jmdict: Arc<Jmdict>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,7 +40,14 @@ pub enum Recalled {
 
 #[component(pub, async)]
 impl SimpleAsyncComponent for TestingScreen {
-	type Init = Db;
+	type Init = (
+		Db,
+		Arc<Dict2>,
+		Arc<Unihan>,
+		Arc<Variants>,
+		Arc<Cedict>,
+		Arc<Jmdict>,
+	);
 	type Input = Message;
 	type Output = OutputMessage;
 
@@ -69,9 +89,37 @@ impl SimpleAsyncComponent for TestingScreen {
 							},
 							gtk::Label {
 								set_css_classes: &["text-5xl"],
+								set_cursor_from_name: Some("pointer"),
+								set_tooltip_text: Some("Show details"),
+								add_controller = gtk::GestureClick {
+									connect_released[sender] => move |_, _, _, _| {
+										sender.input(Message::Inspect(1));
+									},
+								},
 								#[watch]
 								set_label: &model.tests.get(model.current_test.saturating_sub(1)).map_or_else(|| "No previous character.".into(), |q| q.char.to_string())
 							},
+							gtk::Label {
+								set_css_classes: &["dim-label"],
+								set_justify: gtk::Justification::Center,
+								set_wrap: true,
+								#[watch]
+								set_label: &model.current_test.checked_sub(1).and_then(|index| model.tests.get(index)).map(|q| readings_text(&model.lookup(q.char))).unwrap_or_default(),
+							},
+							gtk::Label {
+								set_css_classes: &["dim-label"],
+								set_justify: gtk::Justification::Center,
+								set_wrap: true,
+								#[watch]
+								set_label: &model.current_test.checked_sub(1).and_then(|index| model.tests.get(index)).map(|q| variants_text(&model.variants, q.char)).unwrap_or_default(),
+							},
+							gtk::Label {
+								set_css_classes: &["dim-label"],
+								set_justify: gtk::Justification::Center,
+								set_wrap: true,
+								#[watch]
+								set_label: &model.current_test.checked_sub(1).and_then(|index| model.tests.get(index)).map(|q| example_words_text(&model.cedict, &model.jmdict, q.char)).unwrap_or_default(),
+							},
 							gtk::Button {
 								set_css_classes: &["mt-2"],
 								set_label: "Go back",
@@ -84,6 +132,13 @@ impl SimpleAsyncComponent for TestingScreen {
 				},
 				gtk::Label {
 					set_css_classes: &["card", "text-9xl", "p-8"],
+					set_cursor_from_name: Some("pointer"),
+					set_tooltip_text: Some("Show details"),
+					add_controller = gtk::GestureClick {
+						connect_released[sender] => move |_, _, _, _| {
+							sender.input(Message::Inspect(0));
+						},
+					},
 					#[watch]
 					set_label: &model.tests.get(model.current_test).map_or_else(|| "nil".into(), |q| q.char.to_string())
 				},
@@ -112,13 +167,18 @@ impl SimpleAsyncComponent for TestingScreen {
 	}
 
 	async fn init(
-		db: Self::Init,
+		(db, dict, unihan, variants, cedict, jmdict): Self::Init,
 		widgets: Self::Root,
-		_sender: AsyncComponentSender<Self>,
+		sender: AsyncComponentSender<Self>,
 	) -> AsyncComponentParts<Self> {
 		let model = Self {
 			tests: Vec::new(),
 			db: db.clone(),
+			dict,
+			unihan,
+			variants,
+			cedict,
+			jmdict,
 			current_test: usize::try_from(db.get_test_progress().await.expect("query failed"))
 				.expect("Integer conversion failed"),
 		};
@@ -139,6 +199,17 @@ impl SimpleAsyncComponent for TestingScreen {
 					.output(OutputMessage::Finish(known_chars))
 					.expect("sending finished failed");
 			}
+			Message::Inspect(back) => {
+				if let Some(test) = self
+					.current_test
+					.checked_sub(back)
+					.and_then(|index| self.tests.get(index))
+				{
+					sender
+						.output(OutputMessage::ShowCharacter(test.char))
+						.expect("sending failed");
+				}
+			}
 			Message::GoBack => {
 				self.current_test -= 1;
 				self.db
@@ -160,6 +231,10 @@ impl SimpleAsyncComponent for TestingScreen {
 				};
 				if let Some(c) = self.tests.get_mut(self.current_test) {
 					c.recalled = recalled;
+					self.db
+						.record_answer(c.char, recalled)
+						.await
+						.expect("saving the answer failed");
 					self.current_test += 1;
 					self.db
 						.set_test_progress(self.current_test)
@@ -176,15 +251,86 @@ impl SimpleAsyncComponent for TestingScreen {
 	}
 }
 
+impl TestingScreen {
+	fn lookup(&self, c: char) -> CharInfo<'_> {
+		Lookup::new(&self.dict).with_unihan(&self.unihan).get(c)
+	}
+}
+
+/// The readings of a character by language, like "Mandarin xué · Cantonese hok6 · On ガク",
+/// and its meanings on the next line. Empty for characters neither KANJIDIC2 nor Unihan has.
+pub(crate) fn readings_text(info: &CharInfo) -> String {
+	let readings = [
+		("Mandarin", info.mandarin()),
+		("Cantonese", info.cantonese()),
+		("Korean", info.hangul()),
+		("Vietnamese", info.vietnamese()),
+		("On", info.on()),
+		("Kun", info.kun()),
+	]
+	.into_iter()
+	.filter(|(_, values)| !values.is_empty())
+	.map(|(language, values)| format!("{language} {}", values.join(", ")))
+	.collect::<Vec<_>>()
+	.join(" · ");
+	let meanings = info.meanings().join("; ");
+	[readings, meanings]
+		.into_iter()
+		.filter(|line| !line.is_empty())
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+/// The variants of `c` with what kind they are, like "Traditional 學", or an empty string.
+pub(crate) fn variants_text(variants: &Variants, c: char) -> String {
+	variants
+		.of(c)
+		.iter()
+		.map(|(kind, variant)| format!("{} {variant}", kind.label()))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+/// A few common Chinese words with `c`, like "大学 (da4 xue2), 学生 (xue2 sheng5)", and on the
+/// next line Japanese ones, like "学生 (がくせい)". Empty without any dictionary.
+pub(crate) fn example_words_text(cedict: &Cedict, jmdict: &Jmdict, c: char) -> String {
+	let chinese = cedict
+		.words_with(c, 3)
+		.iter()
+		.map(|word| format!("{} ({})", word.written_with(c), word.pinyin()))
+		.collect::<Vec<_>>()
+		.join(", ");
+	let japanese = jmdict
+		.words_with(c, 3)
+		.iter()
+		.filter_map(|entry| {
+			let form = entry.written_with(c)?;
+			Some(match entry.readings().first() {
+				Some(reading) => format!("{} ({reading})", form.text()),
+				None => form.text().to_owned(),
+			})
+		})
+		.collect::<Vec<_>>()
+		.join(", ");
+	[chinese, japanese]
+		.into_iter()
+		.filter(|line| !line.is_empty())
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
 	StartTest(Vec<Test>),
 	Finish(Vec<char>),
 	Answer(Recalled),
 	GoBack,
+	/// Shows the details of the character this many places before the current one.
+	Inspect(usize),
 }
 
 #[derive(Debug, Clone)]
 pub enum OutputMessage {
 	Finish(Vec<char>),
+	ShowCharacter(char),
 }
@@ -1,15 +1,30 @@
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 
+use lib::cedict::Cedict;
+use lib::ids::{self, Ids};
+use lib::jmdict::Jmdict;
+use lib::load_kanjidic::{bootstrap_dict, Dict2, ParseMode, RadicalType};
+use lib::preset::PresetRegistry;
+use lib::radical::RadicalIndex;
+use lib::unihan::{self, Unihan};
+use lib::variant::Variants;
 use relm4::{adw, adw::prelude::*, component, gtk, prelude::*};
+use tracing::{debug, warn};
 
 const TITLE: &str = "漢tracker";
 
 use crate::db::Db;
-use crate::testing::Test;
+use crate::detail;
+use crate::testing::{Recalled, Test};
 
+use super::components as components_screen;
+use super::coverage as coverage_screen;
 use super::input as input_screen;
+use super::radicals as radicals_screen;
 use super::result as result_screen;
 use super::testing as testing_screen;
 
@@ -19,7 +34,13 @@ pub struct Ht {
 	input_screen: AsyncController<input_screen::InputScreen>,
 	testing_screen: AsyncController<testing_screen::TestingScreen>,
 	result_screen: AsyncController<result_screen::ResultScreen>,
+	coverage_screen: AsyncController<coverage_screen::CoverageScreen>,
+	components_screen: AsyncController<components_screen::ComponentsScreen>,
+	radicals_screen: AsyncController<radicals_screen::RadicalsScreen>,
 	db: Db,
+	dict: Arc<Dict2>,
+	unihan: Arc<Unihan>,
+	variants: Arc<Variants>,
 }
 
 #[component(pub, async)]
@@ -59,6 +80,9 @@ impl SimpleAsyncComponent for Ht {
 						add = model.input_screen.widget(),
 						add = model.testing_screen.widget(),
 						add = model.result_screen.widget(),
+						add = model.coverage_screen.widget(),
+						add = model.components_screen.widget(),
+						add = model.radicals_screen.widget(),
 					},
 				},
 			},
@@ -72,33 +96,101 @@ impl SimpleAsyncComponent for Ht {
 	) -> AsyncComponentParts<Self> {
 		let view_stack = Rc::new(adw::ViewStack::new());
 
+		let dict = Arc::new(
+			bootstrap_dict(project_dirs.to_path_buf()).expect("failed to bootstrap dictionary"),
+		);
+		let unihan = Arc::new(load_unihan(&project_dirs));
+		let presets = Arc::new(PresetRegistry::load(
+			&dict,
+			&unihan,
+			&project_dirs.join("presets"),
+		));
+		let variants = Arc::new(load_variants(&dict, &unihan));
+		let cedict = Arc::new(load_cedict(&project_dirs));
+		let jmdict = Arc::new(load_jmdict(&project_dirs));
+		let ids = Arc::new(load_ids(&project_dirs));
+		let radicals = Arc::new(RadicalIndex::new(&dict, &RadicalType::Classical));
+
 		let input_screen = input_screen::InputScreen::builder()
-			.launch((db.clone(), project_dirs))
+			.launch((
+				db.clone(),
+				Arc::clone(&dict),
+				Arc::clone(&presets),
+				Arc::clone(&variants),
+				Arc::clone(&unihan),
+				Arc::clone(&ids),
+				Arc::clone(&radicals),
+			))
 			.forward(sender.input_sender(), move |msg| match msg {
 				input_screen::OutputMessage::ResumeTest => NextScreen::ResumeTest,
 				input_screen::OutputMessage::NewTest(test) => NextScreen::NewTest(test),
+				input_screen::OutputMessage::BrowseComponents(chars) => {
+					NextScreen::Components(chars)
+				}
+				input_screen::OutputMessage::ShowCharacter(c) => NextScreen::ShowCharacter(c),
 			});
 
 		let testing_screen = testing_screen::TestingScreen::builder()
-			.launch(db.clone())
+			.launch((
+				db.clone(),
+				Arc::clone(&dict),
+				Arc::clone(&unihan),
+				Arc::clone(&variants),
+				Arc::clone(&cedict),
+				Arc::clone(&jmdict),
+			))
 			.forward(sender.input_sender(), |msg| match msg {
 				testing_screen::OutputMessage::Finish(chars) => NextScreen::Results(chars),
+				testing_screen::OutputMessage::ShowCharacter(c) => NextScreen::ShowCharacter(c),
 			});
 
-		let result_screen = result_screen::ResultScreen::builder().launch(()).forward(
-			sender.input_sender(),
-			|msg| match msg {
+		let result_screen = result_screen::ResultScreen::builder()
+			.launch((
+				presets,
+				Arc::clone(&dict),
+				Arc::clone(&unihan),
+				Arc::clone(&variants),
+				cedict,
+				jmdict,
+			))
+			.forward(sender.input_sender(), |msg| match msg {
 				result_screen::OutputMessage::StartOver => NextScreen::Input,
+				result_screen::OutputMessage::CheckText => NextScreen::Coverage,
+				result_screen::OutputMessage::Radicals => NextScreen::Radicals,
+				result_screen::OutputMessage::ShowCharacter(c) => NextScreen::ShowCharacter(c),
 				result_screen::OutputMessage::Exit => NextScreen::Exit,
-			},
-		);
+			});
+
+		let coverage_screen = coverage_screen::CoverageScreen::builder()
+			.launch(())
+			.forward(sender.input_sender(), |msg| match msg {
+				coverage_screen::OutputMessage::Back => NextScreen::ReturnToResults,
+			});
+
+		let components_screen = components_screen::ComponentsScreen::builder()
+			.launch(ids)
+			.forward(sender.input_sender(), |msg| match msg {
+				components_screen::OutputMessage::Back => NextScreen::ReturnToInput,
+			});
+
+		let radicals_screen = radicals_screen::RadicalsScreen::builder()
+			.launch(radicals)
+			.forward(sender.input_sender(), |msg| match msg {
+				radicals_screen::OutputMessage::Back => NextScreen::ReturnToResults,
+			});
 
 		let model = Self {
 			view_stack,
 			input_screen,
 			testing_screen,
 			result_screen,
+			coverage_screen,
+			components_screen,
+			radicals_screen,
 			db,
+			dict,
+			unihan,
+			variants,
 		};
 
 		let view_stack = &*model.view_stack;
@@ -149,6 +241,71 @@ impl SimpleAsyncComponent for Ht {
 					.expect("Shouldn't fail");
 				self.view_stack.set_visible_child(result_screen_widget);
 			}
+			NextScreen::Coverage => {
+				let known = self
+					.db
+					.get_recalled_characters(Recalled::Known)
+					.await
+					.expect("query failed");
+				self.coverage_screen
+					.sender()
+					.send(coverage_screen::Message::SetKnown(
+						known.into_iter().collect(),
+					))
+					.expect("Shouldn't fail");
+				self.view_stack
+					.set_visible_child(self.coverage_screen.widget());
+			}
+			NextScreen::Components(chars) => {
+				let known = self
+					.db
+					.get_recalled_characters(Recalled::Known)
+					.await
+					.expect("query failed");
+				self.components_screen
+					.sender()
+					.send(components_screen::Message::Show(
+						chars,
+						known.into_iter().collect(),
+					))
+					.expect("Shouldn't fail");
+				self.view_stack
+					.set_visible_child(self.components_screen.widget());
+			}
+			NextScreen::Radicals => {
+				let known = self
+					.db
+					.get_recalled_characters(Recalled::Known)
+					.await
+					.expect("query failed");
+				self.radicals_screen
+					.sender()
+					.send(radicals_screen::Message::SetKnown(
+						known.into_iter().collect(),
+					))
+					.expect("Shouldn't fail");
+				self.view_stack
+					.set_visible_child(self.radicals_screen.widget());
+			}
+			NextScreen::ReturnToInput => {
+				self.view_stack
+					.set_visible_child(self.input_screen.widget());
+			}
+			NextScreen::ReturnToResults => {
+				self.view_stack
+					.set_visible_child(self.result_screen.widget());
+			}
+			NextScreen::ShowCharacter(c) => {
+				let history = self.db.answer_history(c).await.expect("query failed");
+				detail::present(
+					&*self.view_stack,
+					&self.dict,
+					&self.unihan,
+					&self.variants,
+					c,
+					&history,
+				);
+			}
 			NextScreen::Exit => {
 				self.db.clone().set_test_progress(0).await.expect("failed");
 				relm4::main_adw_application().quit();
@@ -157,11 +314,106 @@ impl SimpleAsyncComponent for Ht {
 	}
 }
 
+/// The variants in KANJIDIC2 and in the loaded Unihan data.
+fn load_variants(dict: &Dict2, unihan: &Unihan) -> Variants {
+	let mut variants = Variants::from_dict(dict);
+	variants.add_unihan(unihan);
+	variants
+}
+
+/// Every Unihan file in the `unihan` folder of the data directory, or nothing without one.
+fn load_unihan(data_dir: &Path) -> Unihan {
+	let dir = data_dir.join("unihan");
+	match Unihan::load_dir(&dir) {
+		Ok(unihan) => unihan,
+		Err(unihan::Error::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+			debug!("No Unihan data in {}", dir.display());
+			Unihan::new()
+		}
+		Err(e) => {
+			warn!("Failed to load {}: {e}", dir.display());
+			Unihan::new()
+		}
+	}
+}
+
+/// CC-CEDICT from the data directory, as `cedict_ts.u8` or the gzipped download, ranked by
+/// `word_frequency.txt` if that's there too. Empty if there's no dictionary.
+fn load_cedict(data_dir: &Path) -> Cedict {
+	let Some(path) = ["cedict_ts.u8", "cedict_1_0_ts_utf-8_mdbg.txt.gz"]
+		.into_iter()
+		.map(|name| data_dir.join(name))
+		.find(|path| path.exists())
+	else {
+		debug!("No CC-CEDICT in {}", data_dir.display());
+		return Cedict::default();
+	};
+	let mut cedict = match Cedict::load(&path) {
+		Ok(cedict) => cedict,
+		Err(e) => {
+			warn!("Failed to load {}: {e}", path.display());
+			return Cedict::default();
+		}
+	};
+	let path = data_dir.join("word_frequency.txt");
+	match File::open(&path) {
+		Ok(file) => {
+			if let Err(e) = cedict.read_frequencies(BufReader::new(file)) {
+				warn!("Failed to load {}: {e}", path.display());
+			}
+		}
+		Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+		Err(e) => warn!("Failed to open {}: {e}", path.display()),
+	}
+	cedict
+}
+
+/// JMdict from the data directory, gzipped as downloaded or not, preferring the full version
+/// over the English-only one. Empty if there's neither.
+fn load_jmdict(data_dir: &Path) -> Jmdict {
+	let Some(path) = ["JMdict.gz", "JMdict", "JMdict_e.gz", "JMdict_e"]
+		.into_iter()
+		.map(|name| data_dir.join(name))
+		.find(|path| path.exists())
+	else {
+		debug!("No JMdict in {}", data_dir.display());
+		return Jmdict::default();
+	};
+	Jmdict::load(&path, ParseMode::Lenient).unwrap_or_else(|e| {
+		warn!("Failed to load {}: {e}", path.display());
+		Jmdict::default()
+	})
+}
+
+/// Every IDS file in the `ids` folder of the data directory, or nothing without one.
+fn load_ids(data_dir: &Path) -> Ids {
+	let dir = data_dir.join("ids");
+	match Ids::load_dir(&dir) {
+		Ok(ids) => ids,
+		Err(ids::Error::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+			debug!("No IDS data in {}", dir.display());
+			Ids::default()
+		}
+		Err(e) => {
+			warn!("Failed to load {}: {e}", dir.display());
+			Ids::default()
+		}
+	}
+}
+
 #[derive(Debug, Clone)]
 pub enum NextScreen {
 	Input,
 	NewTest(Vec<Test>),
 	ResumeTest,
 	Results(Vec<char>),
+	Coverage,
+	/// Browses the components of these characters.
+	Components(Vec<char>),
+	Radicals,
+	ReturnToInput,
+	ReturnToResults,
+	/// Shows the details of a character over the current screen.
+	ShowCharacter(char),
 	Exit,
 }